@@ -0,0 +1,120 @@
+use std::fmt::{Display, Formatter, Result};
+
+use crate::util::Vec2;
+
+/// Puzzle answer in the form the puzzle expects it to be submitted.
+#[derive(Clone, Debug)]
+pub enum Answer {
+    Int(i64),
+    UInt(u64),
+    Text(String),
+    Coord(Vec2),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match self {
+            Answer::Int(value) => write!(f, "{value}"),
+            Answer::UInt(value) => write!(f, "{value}"),
+            Answer::Text(value) => write!(f, "{value}"),
+            Answer::Coord(Vec2 { x, y }) => write!(f, "{x},{y}"),
+        }
+    }
+}
+
+/// Answers are equal if they would be submitted the same, i.e. compared by their display:
+/// `Int(3)`, `UInt(3)` and `Text("3")` are equal, and so are `Coord(Vec2::new(6, 1))` and
+/// `Text("6,1")`.
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_string() == other.to_string()
+    }
+}
+
+impl PartialEq<i64> for Answer {
+    fn eq(&self, other: &i64) -> bool {
+        match self {
+            Answer::Int(value) => value == other,
+            Answer::UInt(value) => i64::try_from(*value).is_ok_and(|value| value == *other),
+            _ => false,
+        }
+    }
+}
+
+impl PartialEq<&str> for Answer {
+    fn eq(&self, other: &&str) -> bool {
+        self.to_string().as_str() == *other
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(value: i64) -> Self {
+        Answer::Int(value)
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(value: u64) -> Self {
+        Answer::UInt(value)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Answer::UInt(value as u64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_string())
+    }
+}
+
+impl From<Vec2> for Answer {
+    fn from(value: Vec2) -> Self {
+        Answer::Coord(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn coord_display() {
+        assert_eq!(Answer::Coord(Vec2::new(22, 50)).to_string(), "22,50");
+        assert_eq!(Answer::Coord(Vec2::new(-1, 0)).to_string(), "-1,0");
+    }
+
+    #[test]
+    fn equal_across_variants() {
+        let int = Answer::Int(3);
+        let uint = Answer::UInt(3);
+        let text = Answer::from("3");
+
+        assert_eq!(int, uint);
+        assert_eq!(uint, text);
+        assert_eq!(text, int);
+
+        assert_eq!(Answer::Coord(Vec2::new(6, 1)), Answer::from("6,1"));
+        assert_ne!(Answer::Coord(Vec2::new(6, 1)), Answer::from("6, 1"));
+        assert_ne!(Answer::Int(-1), Answer::UInt(u64::MAX));
+        assert_ne!(Answer::from("03"), Answer::Int(3));
+    }
+
+    #[test]
+    fn equal_to_literals() {
+        assert_eq!(Answer::UInt(3), 3);
+        assert_ne!(Answer::UInt(u64::MAX), -1);
+        assert_ne!(Answer::from("3"), 3);
+        assert_eq!(Answer::from(Vec2::new(6, 1)), "6,1");
+        assert_eq!(Answer::Int(-7), "-7");
+    }
+}
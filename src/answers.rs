@@ -0,0 +1,233 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::io::ErrorKind;
+
+use crate::answer::Answer;
+use crate::day;
+use crate::error::{Error, Result};
+
+/// Input column of answers that hold for any input of their day.
+const ANY_INPUT: &str = "*";
+
+struct Entry {
+    day: u8,
+    part: String,
+    input: String,
+    answer: String,
+}
+
+/// Known answers of the puzzles, kept in a local text file.
+///
+/// Each line is `<day> <part> <input> <answer>`, where `<input>` is the hash of the input file
+/// the answer belongs to, or `*` for an answer of any input. Empty lines and lines starting with
+/// `#` are ignored.
+#[derive(Default)]
+pub struct Answers {
+    entries: Vec<Entry>,
+}
+
+impl Answers {
+    /// Loads the answers file, which has to exist.
+    pub fn load(path: &str) -> Result<Self> {
+        let content = fs::read_to_string(path).map_err(|e| match e.kind() {
+            ErrorKind::NotFound => Error::AnswersFileNotFound(path.to_string()),
+            _ => e.into(),
+        })?;
+
+        Self::parse(path, &content)
+    }
+
+    /// Loads the answers file to record answers in, a missing file has no answers yet.
+    pub fn load_or_default(path: &str) -> Result<Self> {
+        match Self::load(path) {
+            Err(Error::AnswersFileNotFound(_)) => Ok(Self::default()),
+            answers => answers,
+        }
+    }
+
+    /// Parses the content of the answers file at `path`.
+    fn parse(path: &str, content: &str) -> Result<Self> {
+        let entries = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+            .map(|(i, line)| {
+                Self::parse_entry(line)
+                    .ok_or_else(|| Error::InvalidAnswersFile(path.to_string(), i + 1))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { entries })
+    }
+
+    fn parse_entry(line: &str) -> Option<Entry> {
+        let mut fields = line.splitn(4, char::is_whitespace);
+
+        let day = fields.next()?.parse().ok()?;
+        let part = fields.next().filter(|&part| part == "1" || part == "2")?;
+        let input = fields.next()?;
+        let answer = fields.next()?.trim();
+
+        (!answer.is_empty()).then(|| Entry {
+            day,
+            part: part.to_string(),
+            input: input.to_string(),
+            answer: answer.to_string(),
+        })
+    }
+
+    /// The known answer of a part, preferring the one recorded for the input with `input_hash`.
+    pub fn expected(&self, day: u8, part: day::Part, input_hash: Option<&str>) -> Option<&str> {
+        let part = part.to_string();
+        let answers = self
+            .entries
+            .iter()
+            .filter(|entry| entry.day == day && entry.part == part);
+
+        let for_input = |input: &str| {
+            answers
+                .clone()
+                .find(|entry| entry.input == input)
+                .map(|entry| entry.answer.as_str())
+        };
+
+        input_hash
+            .and_then(for_input)
+            .or_else(|| for_input(ANY_INPUT))
+    }
+
+    /// Sets the answer of a part, for the input with `input_hash` or for any input.
+    pub fn record(&mut self, day: u8, part: day::Part, input_hash: Option<&str>, answer: &Answer) {
+        let part = part.to_string();
+        let input = input_hash.unwrap_or(ANY_INPUT).to_string();
+
+        self.entries
+            .retain(|entry| !(entry.day == day && entry.part == part && entry.input == input));
+        self.entries.push(Entry {
+            day,
+            part,
+            input,
+            answer: answer.to_string(),
+        });
+    }
+
+    pub fn save(&mut self, path: &str) -> Result<()> {
+        self.entries
+            .sort_by(|a, b| (a.day, &a.part, &a.input).cmp(&(b.day, &b.part, &b.input)));
+
+        let mut content = String::from("# day part input answer\n");
+        for Entry {
+            day,
+            part,
+            input,
+            answer,
+        } in &self.entries
+        {
+            let _ = writeln!(content, "{day} {part} {input} {answer}");
+        }
+
+        Ok(fs::write(path, content)?)
+    }
+}
+
+/// Hash identifying an input file in the answers file, 64-bit FNV-1a as hex.
+pub fn input_hash(bytes: &[u8]) -> String {
+    let hash = bytes.iter().fold(0xcbf29ce484222325_u64, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+
+    format!("{hash:016x}")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day::Part;
+
+    /// A path in the temp directory unique to the test `name`.
+    fn temp_path(name: &str) -> String {
+        let file = format!("aoc2024-{}-{name}.txt", std::process::id());
+        std::env::temp_dir()
+            .join(file)
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn expected_by_input_hash() {
+        let content = "\
+# day part input answer
+1 1 * 11
+1 1 abcd 12
+1 2 abcd 2 words
+";
+        let answers = Answers::parse("answers.txt", content).unwrap();
+
+        assert_eq!(answers.expected(1, Part::One, Some("abcd")), Some("12"));
+        assert_eq!(answers.expected(1, Part::One, Some("ef01")), Some("11"));
+        assert_eq!(answers.expected(1, Part::One, None), Some("11"));
+        assert_eq!(
+            answers.expected(1, Part::Two, Some("abcd")),
+            Some("2 words")
+        );
+        assert_eq!(answers.expected(1, Part::Two, Some("ef01")), None);
+        assert_eq!(answers.expected(2, Part::One, None), None);
+    }
+
+    #[test]
+    fn invalid_lines() {
+        for (content, line) in [("1 1 *\n", 1), ("\n1 3 * 5\n", 2), ("x 1 * 5\n", 1)] {
+            assert!(matches!(
+                Answers::parse("answers.txt", content),
+                Err(Error::InvalidAnswersFile(_, l)) if l == line
+            ));
+        }
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = temp_path("save_and_load");
+
+        let mut answers = Answers::default();
+        answers.record(2, Part::One, Some("abcd"), &Answer::Int(7));
+        answers.record(
+            1,
+            Part::Two,
+            None,
+            &Answer::Coord(crate::util::Vec2::new(22, 50)),
+        );
+        answers.record(2, Part::One, Some("abcd"), &Answer::UInt(8));
+        answers.save(&path).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        let loaded = Answers::load(&path);
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(
+            content,
+            "# day part input answer\n1 2 * 22,50\n2 1 abcd 8\n"
+        );
+
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.expected(1, Part::Two, Some("abcd")), Some("22,50"));
+        assert_eq!(loaded.expected(2, Part::One, Some("abcd")), Some("8"));
+    }
+
+    #[test]
+    fn missing_file() {
+        let path = temp_path("missing_file");
+
+        assert!(matches!(
+            Answers::load(&path),
+            Err(Error::AnswersFileNotFound(p)) if p == path
+        ));
+        assert!(Answers::load_or_default(&path).unwrap().entries.is_empty());
+    }
+
+    #[test]
+    fn hashes() {
+        assert_eq!(input_hash(b""), "cbf29ce484222325");
+        assert_eq!(input_hash(b"a"), "af63dc4c8601ec8c");
+        assert_ne!(input_hash(b"1,2\n"), input_hash(b"2,1\n"));
+    }
+}
@@ -0,0 +1,265 @@
+use std::fmt::{self, Display, Formatter};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::day;
+use crate::error::Result;
+use crate::input::Input;
+use crate::params::Params;
+use crate::solution::DynSolution;
+use crate::util::Table;
+
+/// Timing statistics of repeated runs of a single stage.
+#[derive(Clone, Copy, Debug)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    fn from_samples(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "at least one run is needed");
+
+        samples.sort();
+
+        let runs = samples.len();
+        let median = if runs.is_multiple_of(2) {
+            (samples[runs / 2 - 1] + samples[runs / 2]) / 2
+        } else {
+            samples[runs / 2]
+        };
+
+        Self {
+            runs,
+            min: samples[0],
+            median,
+            mean: samples.iter().sum::<Duration>() / runs as u32,
+            max: samples[runs - 1],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Stage {
+    Parse,
+    Solve(day::Part),
+}
+
+impl Display for Stage {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Stage::Parse => write!(f, "parse"),
+            Stage::Solve(part) => write!(f, "part {part}"),
+        }
+    }
+}
+
+pub struct BenchResult {
+    pub day: u8,
+    pub stage: Stage,
+    pub stats: Stats,
+}
+
+/// Parses the day's input `runs` times and then solves each of `parts` `runs` times.
+///
+/// The input is opened anew for every parse, as parsing consumes it.
+pub fn bench(
+    solution: &dyn DynSolution,
+    open_input: impl Fn() -> Result<Input>,
+    params: &Params,
+    parts: &[day::Part],
+    runs: usize,
+) -> Result<Vec<BenchResult>> {
+    let mut samples = Vec::with_capacity(runs);
+    let mut model = None;
+
+    for _ in 0..runs {
+        let input = open_input()?;
+
+        let start = Instant::now();
+        model = Some(solution.parse(input, params)?);
+        samples.push(start.elapsed());
+    }
+
+    let Some(model) = model else {
+        return Ok(Vec::new());
+    };
+
+    let mut results = vec![BenchResult {
+        day: solution.day(),
+        stage: Stage::Parse,
+        stats: Stats::from_samples(samples),
+    }];
+
+    for &part in parts {
+        let mut samples = Vec::with_capacity(runs);
+
+        for _ in 0..runs {
+            let start = Instant::now();
+            solution.solve(model.as_ref(), part)?;
+            samples.push(start.elapsed());
+        }
+
+        results.push(BenchResult {
+            day: solution.day(),
+            stage: Stage::Solve(part),
+            stats: Stats::from_samples(samples),
+        });
+    }
+
+    Ok(results)
+}
+
+pub fn report(results: &[BenchResult]) -> Table<7> {
+    let mut table = Table::new(["Day", "Stage", "Runs", "Min", "Median", "Mean", "Max"]);
+
+    for BenchResult { day, stage, stats } in results {
+        table.push([
+            day.to_string(),
+            stage.to_string(),
+            stats.runs.to_string(),
+            format!("{:.2?}", stats.min),
+            format!("{:.2?}", stats.median),
+            format!("{:.2?}", stats.mean),
+            format!("{:.2?}", stats.max),
+        ]);
+    }
+
+    table
+}
+
+/// Appends the results to a CSV file, writing the header first if the file is new.
+///
+/// All rows of one call share a timestamp, so separate runs can be told apart and compared.
+pub fn write_csv(path: &str, results: &[BenchResult]) -> Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+
+    if file.metadata()?.len() == 0 {
+        writeln!(
+            file,
+            "timestamp,day,stage,runs,min_ns,median_ns,mean_ns,max_ns"
+        )?;
+    }
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |t| t.as_secs());
+
+    for BenchResult { day, stage, stats } in results {
+        writeln!(
+            file,
+            "{timestamp},{day},{stage},{},{},{},{},{}",
+            stats.runs,
+            stats.min.as_nanos(),
+            stats.median.as_nanos(),
+            stats.mean.as_nanos(),
+            stats.max.as_nanos()
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day::Part;
+
+    fn millis(samples: &[u64]) -> Vec<Duration> {
+        samples
+            .iter()
+            .map(|&ms| Duration::from_millis(ms))
+            .collect()
+    }
+
+    #[test]
+    fn median_of_odd_runs() {
+        let stats = Stats::from_samples(millis(&[9, 1, 5]));
+
+        assert_eq!(stats.runs, 3);
+        assert_eq!(stats.median, Duration::from_millis(5));
+    }
+
+    #[test]
+    fn median_of_even_runs() {
+        let stats = Stats::from_samples(millis(&[8, 1, 4, 2]));
+
+        assert_eq!(stats.median, Duration::from_millis(3));
+    }
+
+    #[test]
+    fn min_mean_max() {
+        let stats = Stats::from_samples(millis(&[8, 1, 4, 3]));
+
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.mean, Duration::from_millis(4));
+        assert_eq!(stats.max, Duration::from_millis(8));
+    }
+
+    #[test]
+    fn single_run() {
+        let stats = Stats::from_samples(millis(&[7]));
+        let run = Duration::from_millis(7);
+
+        assert_eq!(stats.runs, 1);
+        assert_eq!(
+            (stats.min, stats.median, stats.mean, stats.max),
+            (run, run, run, run)
+        );
+    }
+
+    #[test]
+    fn csv_header_written_once() {
+        let path = std::env::temp_dir()
+            .join(format!("aoc2024-{}-bench.csv", std::process::id()))
+            .to_string_lossy()
+            .into_owned();
+        let results = [
+            BenchResult {
+                day: 3,
+                stage: Stage::Parse,
+                stats: Stats::from_samples(millis(&[1, 3])),
+            },
+            BenchResult {
+                day: 3,
+                stage: Stage::Solve(Part::Two),
+                stats: Stats::from_samples(millis(&[5])),
+            },
+        ];
+
+        write_csv(&path, &results).unwrap();
+        write_csv(&path, &results[1..]).unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            lines[0],
+            "timestamp,day,stage,runs,min_ns,median_ns,mean_ns,max_ns"
+        );
+
+        let rows = lines[1..]
+            .iter()
+            .map(|line| line.split_once(',').unwrap().1)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            [
+                "3,parse,2,1000000,2000000,2000000,3000000",
+                "3,part 2,1,5000000,5000000,5000000,5000000",
+                "3,part 2,1,5000000,5000000,5000000,5000000",
+            ]
+        );
+        assert!(lines[1..].iter().all(|line| line
+            .split(',')
+            .next()
+            .unwrap()
+            .parse::<u64>()
+            .is_ok()));
+    }
+}
@@ -1,74 +1,258 @@
 use aoc2024::*;
 
+use crate::answers::Answers;
 use crate::error::{ArgumentError, Error, Result};
 use crate::input::Input;
-use crate::util::{construct_filename, parse_day, parse_part, validate_day, validate_part};
+use crate::runner::{Status, Summary};
+use crate::util::{parallel, usage, Args};
 
-use env_logger;
-use log::info;
+use log::{error, info};
 use std::env;
+use std::fs;
+use std::process::ExitCode;
+use std::time::Instant;
 
-fn run(day: u8, part: day::Part, input_file: Option<String>) -> Result<()> {
-    let input = Input::from_file(&input_file.unwrap_or_else(|| construct_filename(day, part)))
-        .or_else(|_| Input::from_file(&"/dev/stdin"))?;
-
-    info!("Day {day}|{part} 🎄");
-
-    let result = match day {
-        0 => day_0::run(input, part),
-        1 => day_1::run(input, part),
-        2 => day_2::run(input, part),
-        3 => day_3::run(input, part),
-        4 => day_4::run(input, part),
-        5 => day_5::run(input, part),
-        6 => day_6::run(input, part),
-        7 => day_7::run(input, part),
-        8 => day_8::run(input, part),
-        9 => day_9::run(input, part),
-        10 => day_10::run(input, part),
-        11 => day_11::run(input, part),
-        12 => day_12::run(input, part),
-        13 => day_13::run(input, part),
-        14 => day_14::run(input, part),
-        15 => day_15::run(input, part),
-        16 => day_16::run(input, part),
-        17 => day_17::run(input, part),
-        18 => day_18::run(input, part),
-        19 => day_19::run(input, part),
-        20 => day_20::run(input, part),
-        21 => day_21::run(input, part),
-        22 => day_22::run(input, part),
-        23 => day_23::run(input, part),
-        24 => day_24::run(input, part),
-        25 => day_25::run(input, part),
-        _ => Err(Error::DayNotImplemented(day)),
-    }?;
-    println!("{}", result);
-
-    info!("Day {day}|{part} done 🌟");
+fn open_input(args: &Args, day: u8) -> Result<Input> {
+    if args.stdin {
+        Input::from_stdin()
+    } else {
+        Input::from_file(&args.input_file(day))
+    }
+}
+
+/// Names the input of `day` in diagnostics.
+fn input_origin(args: &Args, day: u8) -> String {
+    if args.stdin {
+        "<stdin>".to_string()
+    } else {
+        args.input_file(day)
+    }
+}
+
+/// Hash of the input file of `day`, keying its answers in the answers file. Stdin has none.
+fn input_hash(args: &Args, day: u8) -> Option<String> {
+    if args.stdin {
+        return None;
+    }
+
+    fs::read(args.input_file(day))
+        .ok()
+        .map(|bytes| answers::input_hash(&bytes))
+}
+
+fn run(args: &Args, day: u8, part: day::Part) -> Result<()> {
+    let input = open_input(args, day)?;
+
+    let solution = solution::find(day).ok_or(Error::DayNotImplemented(day))?;
+
+    info!("Day {day}|{part} {} 🎄", solution.title());
+
+    let day_run = runner::run(solution, input, &args.params, &[part])?;
+    info!("Day {day} parsed in {:?}", day_run.parse_time);
+
+    for part_run in day_run.parts {
+        println!("{}", part_run.answer?);
+
+        info!(
+            "Day {day}|{} done in {:?} 🌟",
+            part_run.part, part_run.solve_time
+        );
+    }
+
+    Ok(())
+}
+
+fn run_all(args: &Args) -> Result<()> {
+    let start = Instant::now();
+    let mut summary = Summary::new();
+    let parts = args.parts();
+
+    let answers_file = args.answers.as_deref().unwrap_or("answers.txt");
+    let mut answers = if args.check {
+        Answers::load(answers_file)?
+    } else if args.record {
+        Answers::load_or_default(answers_file)?
+    } else {
+        Answers::default()
+    };
+
+    for &day in &args.days {
+        let Some(solution) = solution::find(day) else {
+            summary.add_not_implemented(day, &parts);
+            continue;
+        };
+
+        info!("Day {day} {} 🎄", solution.title());
+
+        match open_input(args, day)
+            .and_then(|input| runner::run(solution, input, &args.params, &parts))
+        {
+            Ok(day_run) => {
+                let hash = input_hash(args, day);
+
+                if args.record {
+                    for part_run in &day_run.parts {
+                        if let Ok(answer) = &part_run.answer {
+                            answers.record(day, part_run.part, hash.as_deref(), answer);
+                        }
+                    }
+                }
+
+                summary.add(day_run);
+
+                if args.check {
+                    summary.check(day, |part| {
+                        answers
+                            .expected(day, part, hash.as_deref())
+                            .map(str::to_string)
+                    });
+                }
+            }
+            Err(e) => {
+                if let Error::Parse(e) = &e {
+                    eprint!("{}", e.diagnostic(&input_origin(args, day)));
+                }
+                summary.add_failed(day, &parts, &e);
+            }
+        }
+    }
+
+    print!("{summary}");
+
+    if args.check {
+        print!(
+            "{} correct, {} wrong, {} without an answer, ",
+            summary.count(Status::Correct),
+            summary.count(Status::Wrong),
+            summary.count(Status::Unchecked)
+        );
+    }
+    println!(
+        "{} ok, {} failed, {} not implemented in {:.2?}",
+        summary.count(Status::Ok),
+        summary.count(Status::Failed),
+        summary.count(Status::NotImplemented),
+        start.elapsed()
+    );
+
+    if args.record {
+        answers.save(answers_file)?;
+        info!("Answers recorded in {answers_file}");
+    }
+
+    match (
+        summary.count(Status::Failed),
+        summary.count(Status::Wrong),
+        summary.count(Status::Unchecked),
+    ) {
+        (0, 0, 0) => Ok(()),
+        (0, 0, missing) => Err(Error::MissingAnswers(missing)),
+        (0, wrong, _) => Err(Error::WrongAnswers(wrong)),
+        (failed, _, _) => Err(Error::FailedParts(failed)),
+    }
+}
+
+fn run_bench(args: &Args, runs: usize) -> Result<()> {
+    let mut results = Vec::new();
+    let parts = args.parts();
+
+    for &day in &args.days {
+        let Some(solution) = solution::find(day) else {
+            error!("Day {day} not implemented");
+            continue;
+        };
+
+        info!("Day {day} {} ⏱️", solution.title());
+
+        match bench::bench(
+            solution,
+            || open_input(args, day),
+            &args.params,
+            &parts,
+            runs,
+        ) {
+            Ok(day_results) => results.extend(day_results),
+            Err(e) => error!("Day {day} failed: {e}"),
+        }
+    }
+
+    let output = args.bench_output.as_deref().unwrap_or("bench.csv");
+
+    print!("{}", bench::report(&results));
+    bench::write_csv(output, &results)?;
+
+    info!("Benchmark results appended to {output}");
+
+    Ok(())
+}
+
+fn new_day(day: u8) -> Result<()> {
+    for path in scaffold::new_day(&env::current_dir()?, day)? {
+        println!("Wrote {}", path.display());
+    }
 
     Ok(())
 }
 
-fn main() -> Result<()> {
-    env_logger::init();
+fn try_main(args: &Args) -> Result<()> {
+    if let Some(day) = args.new {
+        return new_day(day);
+    }
 
-    let mut args = env::args().skip(1);
+    let solutions = args
+        .days
+        .iter()
+        .filter_map(|&day| solution::find(day))
+        .collect::<Vec<_>>();
+
+    if let Some(name) = args.params.find_unknown(&solutions) {
+        Err(ArgumentError::UnknownParam(name.to_string()))?
+    }
+
+    if let Some(threads) = args.threads {
+        parallel::set_threads(threads);
+    }
+
+    if let Some(runs) = args.bench {
+        return run_bench(args, runs);
+    }
+
+    match (args.days.as_slice(), args.part) {
+        (&[day], Some(part)) if !args.check && !args.record => run(args, day, part),
+        _ => run_all(args),
+    }
+}
 
-    let day = args
-        .next()
-        .ok_or(ArgumentError::MissingArgument("day").into())
-        .and_then(parse_day)
-        .and_then(validate_day)?;
+fn main() -> ExitCode {
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("error: {e}\n\nFor more information, try '--help'.");
+            return ExitCode::FAILURE;
+        }
+    };
 
-    let part = args
-        .next()
-        .or(Some("0".to_string()))
-        .ok_or(ArgumentError::MissingArgument("part").into())
-        .and_then(parse_part)
-        .and_then(validate_part)?;
+    if args.help {
+        print!("{}", usage());
+        return ExitCode::SUCCESS;
+    }
 
-    let infile = args.next();
+    let default_level = if args.verbose { "info" } else { "warn" };
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(default_level))
+        .init();
 
-    run(day, part, infile)
+    match try_main(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(Error::Parse(e)) => {
+            let origin = e
+                .day
+                .map_or("<input>".to_string(), |day| input_origin(&args, day));
+            eprint!("{}", e.diagnostic(&origin));
+            ExitCode::FAILURE
+        }
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
 }
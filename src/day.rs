@@ -1,4 +1,5 @@
 use std::fmt::{Display, Formatter, Result};
+use std::io::Write;
 
 #[derive(Copy, Clone, Debug)]
 pub enum Part {
@@ -15,28 +16,79 @@ impl Display for Part {
     }
 }
 
+/// Opens a private puzzle input for the real-input tests, `None` if it isn't there.
+///
+/// The inputs are not part of the repository, so a missing one skips the tests instead of
+/// failing them. The notice goes to stderr directly, the test harness captures `eprintln!`.
+#[doc(hidden)]
+pub fn test_input(file: &str) -> Option<crate::input::Input> {
+    let path = format!("input/{file}");
+
+    match crate::input::Input::from_file(&path) {
+        Ok(input) => Some(input),
+        Err(_) => {
+            let _ = writeln!(std::io::stderr(), "skipping test, {path} not found");
+            None
+        }
+    }
+}
+
+/// Tests of a day's `Solution` against the puzzle input in `input/`, skipped when it's missing.
 #[macro_export]
 macro_rules! day_tests {
     ($day:expr, $part1_result:expr, $part2_result:expr) => {
         #[cfg(test)]
         mod test {
             use super::*;
-
-            fn input() -> Input {
-                crate::input::Input::from_file(format!("input/{}", $day).as_str()).unwrap()
-            }
+            use $crate::solution::DynSolution;
 
             #[test]
             fn part_one() {
-                let result = run(input(), day::Part::One).unwrap();
+                let Some(input) = $crate::day::test_input($day) else {
+                    return;
+                };
+                let result = Day
+                    .run(input, &Default::default(), $crate::day::Part::One)
+                    .unwrap();
                 assert_eq!(result, $part1_result);
             }
 
             #[test]
             fn part_two() {
-                let result = run(input(), day::Part::Two).unwrap();
+                let Some(input) = $crate::day::test_input($day) else {
+                    return;
+                };
+                let result = Day
+                    .run(input, &Default::default(), $crate::day::Part::Two)
+                    .unwrap();
                 assert_eq!(result, $part2_result);
             }
         }
     };
 }
+
+/// Tests of a day's `Solution` against inline example inputs, these always run.
+///
+/// Each case is `test_name: part_fn(input) => expected answer`, e.g.
+/// `part_one: part_one(EXAMPLE) => 11`. Parameters of the day can be overridden after the input,
+/// e.g. `part_one: part_one(EXAMPLE, width = 7, height = 7) => 22`.
+#[macro_export]
+macro_rules! example_tests {
+    ($($name:ident: $part:ident($input:expr $(, $param:ident = $value:expr)*) => $expected:expr),* $(,)?) => {
+        #[cfg(test)]
+        mod example_test {
+            use super::*;
+            use $crate::solution::Solution;
+
+            $(
+                #[test]
+                fn $name() {
+                    let params = $crate::params::Params::new()$(.with(stringify!($param), $value))*;
+                    let model = Day::parse($crate::input::Input::from_str($input), &params).unwrap();
+                    let result = Day::$part(&model).unwrap();
+                    assert_eq!(result, $expected);
+                }
+            )*
+        }
+    };
+}
@@ -1,18 +1,50 @@
+use crate::answer::Answer;
 use crate::error::Result;
 use crate::input::Input;
-use crate::{day, day_tests};
+use crate::params::Params;
+use crate::solution::Solution;
+use crate::util::parse::{self, Line};
+use crate::{day_tests, example_tests};
 
 #[allow(unused_imports)]
 use log::info;
 
-#[allow(unreachable_code, unused_variables)]
-pub fn run(input: Input, part: day::Part) -> Result<i64> {
-    let result = match part {
-        day::Part::One => 0,
-        day::Part::Two => 0,
-    } as i64;
+#[allow(dead_code)]
+pub struct Model {
+    lines: Vec<Line>,
+}
+
+pub struct Day;
+
+#[allow(unused_variables)]
+impl Solution for Day {
+    type Model = Model;
+
+    const DAY: u8 = 0;
+    const TITLE: &'static str = "Template";
+
+    fn parse(mut input: Input, _: &Params) -> Result<Self::Model> {
+        // parse each line here, its methods report errors pointing at the offending part
+        let lines = parse::lines(&mut input).collect();
+
+        Ok(Model { lines })
+    }
+
+    fn part_one(model: &Self::Model) -> Result<Answer> {
+        Ok(0_i64.into())
+    }
+
+    fn part_two(model: &Self::Model) -> Result<Answer> {
+        Ok(0_i64.into())
+    }
+}
+
+#[cfg(test)]
+const EXAMPLE: &str = "";
 
-    Ok(result)
+example_tests! {
+    part_one: part_one(EXAMPLE) => 0,
+    part_two: part_two(EXAMPLE) => 0,
 }
 
 day_tests!("day_0-1.dat", 0, 0);
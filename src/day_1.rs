@@ -1,6 +1,9 @@
-use crate::error::Result;
+use crate::answer::Answer;
+use crate::error::{ParseError, Result};
 use crate::input::Input;
-use crate::{day, day_tests};
+use crate::params::Params;
+use crate::solution::Solution;
+use crate::{day_tests, example_tests};
 
 use std::collections::BTreeMap;
 
@@ -12,31 +15,40 @@ impl InputReader {
     fn new(input: Input) -> Self {
         Self { input }
     }
+
+    fn parse_line(line_no: usize, line: &str) -> Result<(i64, i64)> {
+        let mut parts = line.split_whitespace();
+        let mut location_id = || -> Result<i64> {
+            let part = parts.next().unwrap_or("");
+            part.parse()
+                .map_err(|_| ParseError::at(line_no, line, part, "a location ID").into())
+        };
+
+        Ok((location_id()?, location_id()?))
+    }
 }
 
 impl Iterator for InputReader {
-    type Item = (i64, i64);
+    type Item = Result<(i64, i64)>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let line = self.input.read_line()?;
-        let mut parts = line.split_whitespace();
-        let a = parts.next()?.parse().ok()?;
-        let b = parts.next()?.parse().ok()?;
-        Some((a, b))
+        Some(Self::parse_line(self.input.line_number(), &line))
     }
 }
 
-struct Locations {
+pub struct Locations {
     left: Vec<i64>,
     right: Vec<i64>,
 }
 
 impl Locations {
-    fn new(input: Input) -> Self {
+    fn new(input: Input) -> Result<Self> {
         let mut left = Vec::new();
         let mut right = Vec::new();
 
-        for (a, b) in InputReader::new(input) {
+        for pair in InputReader::new(input) {
+            let (a, b) = pair?;
             left.push(a);
             right.push(b);
         }
@@ -44,7 +56,7 @@ impl Locations {
         left.sort();
         right.sort();
 
-        Self { left, right }
+        Ok(Self { left, right })
     }
 
     // task #1
@@ -68,7 +80,7 @@ impl Locations {
 
     // build frequency map version 1
     #[allow(dead_code)]
-    fn build_frequency_map_1(list: &Vec<i64>) -> BTreeMap<i64, i64> {
+    fn build_frequency_map_1(list: &[i64]) -> BTreeMap<i64, i64> {
         list.iter()
             .fold(Vec::<(i64, i64)>::new(), |mut acc, x| {
                 if let Some((value, freq)) = acc.last_mut() {
@@ -80,7 +92,7 @@ impl Locations {
                 } else {
                     acc.push((*x, 1));
                 }
-                return acc;
+                acc
             })
             .into_iter()
             .collect()
@@ -88,7 +100,7 @@ impl Locations {
 
     // build frequency map version 2
     #[allow(dead_code)]
-    fn build_frequency_map_2(list: &Vec<i64>) -> BTreeMap<i64, i64> {
+    fn build_frequency_map_2(list: &[i64]) -> BTreeMap<i64, i64> {
         let mut freq = BTreeMap::new();
         for x in list {
             freq.entry(*x).and_modify(|e| *e += 1).or_insert(1);
@@ -97,15 +109,40 @@ impl Locations {
     }
 }
 
-pub fn run(input: Input, part: day::Part) -> Result<i64> {
-    let locations = Locations::new(input);
+pub struct Day;
+
+impl Solution for Day {
+    type Model = Locations;
 
-    let result = match part {
-        day::Part::One => locations.lists_distance(),
-        day::Part::Two => locations.similarity_score(),
-    };
+    const DAY: u8 = 1;
+    const TITLE: &'static str = "Historian Hysteria";
+
+    fn parse(input: Input, _: &Params) -> Result<Self::Model> {
+        Locations::new(input)
+    }
+
+    fn part_one(locations: &Self::Model) -> Result<Answer> {
+        Ok(locations.lists_distance().into())
+    }
+
+    fn part_two(locations: &Self::Model) -> Result<Answer> {
+        Ok(locations.similarity_score().into())
+    }
+}
 
-    Ok(result)
+#[cfg(test)]
+const EXAMPLE: &str = "\
+3   4
+4   3
+2   5
+1   3
+3   9
+3   3
+";
+
+example_tests! {
+    part_one: part_one(EXAMPLE) => 11,
+    part_two: part_two(EXAMPLE) => 31,
 }
 
 day_tests!("day_1-1.dat", 1938424, 22014209);
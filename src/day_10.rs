@@ -1,104 +1,53 @@
 use std::collections::{HashSet, VecDeque};
 
+use crate::answer::Answer;
 use crate::error::Result;
 use crate::input::Input;
-use crate::{day, day_tests};
-
-#[derive(Clone, Copy, Eq, Hash, PartialEq)]
-struct Pos {
-    x: i64,
-    y: i64,
-}
-
-impl std::fmt::Debug for Pos {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {})", self.x, self.y)
-    }
-}
-
-impl std::ops::Add<(i64, i64)> for Pos {
-    type Output = Pos;
-
-    fn add(self, rhs: (i64, i64)) -> Pos {
-        Pos {
-            x: self.x + rhs.0,
-            y: self.y + rhs.1,
-        }
-    }
-}
+use crate::params::Params;
+use crate::solution::Solution;
+use crate::util::grid::Grid;
+use crate::util::Vec2;
+use crate::{day_tests, example_tests};
 
 type Height = u8;
 
-struct TopographicMap {
-    map: Vec<Vec<Height>>,
-    trailheads: Vec<Pos>,
+pub struct TopographicMap {
+    map: Grid<Height>,
+    trailheads: Vec<Vec2>,
 }
 
 impl TopographicMap {
-    fn new(input: Input) -> Self {
-        let mut trailheads = Vec::<Pos>::new();
-
-        let map = input
-            .lines()
-            .enumerate()
-            .map(|(y, line)| {
-                line.expect("valid input")
-                    .char_indices()
-                    .map(|(x, c)| {
-                        let h = c.to_digit(10).expect("valid digit") as Height;
-                        if h == 0 {
-                            trailheads.push(Pos {
-                                x: x as i64,
-                                y: y as i64,
-                            });
-                        }
-                        h
-                    })
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<_>>();
-
-        Self { map, trailheads }
-    }
+    fn new(mut input: Input) -> Result<Self> {
+        let (map, markers) = Grid::parse(&mut input, &['0'], |c| {
+            c.to_digit(10).map(|h| h as Height).ok_or("a height digit")
+        })?;
 
-    fn height_at(&self, pos: Pos) -> Option<Height> {
-        let Pos { x, y } = pos;
-        self.map
-            .get(y as usize)
-            .map_or(None, |row| row.get(x as usize))
-            .copied()
-    }
+        let trailheads = markers.all('0').collect();
 
-    fn neighbours(&self, pos: Pos) -> [Pos; 4] {
-        [pos + (0, -1), pos + (0, 1), pos + (-1, 0), pos + (1, 0)]
+        Ok(Self { map, trailheads })
     }
 
     // number of peaks (height 9) reachable from a given trailhead
-    fn reachable_peaks(&self, trailhead: Pos) -> usize {
+    fn reachable_peaks(&self, trailhead: Vec2) -> usize {
         let mut peaks = 0;
 
-        let mut visited = HashSet::<Pos>::new();
-        let mut exploring = VecDeque::<Pos>::new();
+        let mut visited = HashSet::<Vec2>::new();
+        let mut exploring = VecDeque::<Vec2>::new();
 
         exploring.push_back(trailhead);
         visited.insert(trailhead);
 
         while let Some(pos) = exploring.pop_front() {
-            let h0 = self.height_at(pos).unwrap_or(0);
-
-            for neighbour in self.neighbours(pos) {
-                match self.height_at(neighbour) {
-                    Some(h) if h == (h0 + 1) => {
-                        if !visited.insert(neighbour) {
-                            continue;
-                        }
-                        if h == 9 {
-                            peaks += 1;
-                        } else {
-                            exploring.push_back(neighbour);
-                        }
-                    }
-                    _ => {}
+            let h0 = self.map[pos];
+
+            for (neighbour, &h) in self.map.neighbours4(pos) {
+                if h != h0 + 1 || !visited.insert(neighbour) {
+                    continue;
+                }
+                if h == 9 {
+                    peaks += 1;
+                } else {
+                    exploring.push_back(neighbour);
                 }
             }
         }
@@ -107,24 +56,21 @@ impl TopographicMap {
     }
 
     // number of distinct routes from a given trailhead to a peak
-    fn trail_rating(&self, trailhead: Pos) -> usize {
+    fn trail_rating(&self, trailhead: Vec2) -> usize {
         let mut distinct_routes = 0;
         let mut exploring = vec![trailhead];
 
         while let Some(pos) = exploring.pop() {
-            let h0 = self.height_at(pos).unwrap_or(0);
-
-            for neighbour in self.neighbours(pos) {
-                match self.height_at(neighbour) {
-                    Some(h) if h == (h0 + 1) => {
-                        if h == 9 {
-                            distinct_routes += 1;
-                            continue;
-                        } else {
-                            exploring.push(neighbour);
-                        }
-                    }
-                    _ => {}
+            let h0 = self.map[pos];
+
+            for (neighbour, &h) in self.map.neighbours4(pos) {
+                if h != h0 + 1 {
+                    continue;
+                }
+                if h == 9 {
+                    distinct_routes += 1;
+                } else {
+                    exploring.push(neighbour);
                 }
             }
         }
@@ -147,16 +93,42 @@ impl TopographicMap {
     }
 }
 
-#[allow(unreachable_code, unused_variables)]
-pub fn run(input: Input, part: day::Part) -> Result<i64> {
-    let map = TopographicMap::new(input);
+pub struct Day;
+
+impl Solution for Day {
+    type Model = TopographicMap;
+
+    const DAY: u8 = 10;
+    const TITLE: &'static str = "Hoof It";
 
-    let result = match part {
-        day::Part::One => map.expolore_peaks(),
-        day::Part::Two => map.expolore_ratings(),
-    } as i64;
+    fn parse(input: Input, _: &Params) -> Result<Self::Model> {
+        TopographicMap::new(input)
+    }
+
+    fn part_one(map: &Self::Model) -> Result<Answer> {
+        Ok(map.expolore_peaks().into())
+    }
+
+    fn part_two(map: &Self::Model) -> Result<Answer> {
+        Ok(map.expolore_ratings().into())
+    }
+}
 
-    Ok(result)
+#[cfg(test)]
+const EXAMPLE: &str = "\
+89010123
+78121874
+87430965
+96549874
+45678903
+32019012
+01329801
+10456732
+";
+
+example_tests! {
+    part_one: part_one(EXAMPLE) => 36,
+    part_two: part_two(EXAMPLE) => 81,
 }
 
 day_tests!("day_10-1.dat", 816, 1960);
@@ -1,9 +1,15 @@
-use crate::error::Result;
+use crate::answer::Answer;
+use crate::error::{ParseError, Result};
 use crate::input::Input;
-use crate::{day, day_tests};
+use crate::params::{Param, ParamSpec, Params};
+use crate::solution::Solution;
+use crate::{day_tests, example_tests};
 
 use std::collections::HashMap;
 
+const BLINKS_ONE: Param<u64> = Param::new("blinks_one", 25);
+const BLINKS_TWO: Param<u64> = Param::new("blinks_two", 75);
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 struct PebbleCacheKey {
     pebble: u64,
@@ -19,24 +25,32 @@ impl PebbleCacheKey {
     }
 }
 
-struct PlutonianPebbles {
+#[derive(Clone)]
+pub struct PlutonianPebbles {
     pebbles: Vec<u64>,
     cache: HashMap<PebbleCacheKey, usize>, // pebble after n blinks -> count
+    blinks: (u64, u64),                    // blink counts of part one and two
 }
 
 impl PlutonianPebbles {
-    fn new(mut input: Input) -> Self {
-        let pebbles = input
+    fn new(mut input: Input, blinks: (u64, u64)) -> Result<Self> {
+        let line = input
             .read_line()
-            .expect("valid input")
+            .ok_or_else(|| ParseError::eof(1, "a line of pebbles"))?;
+
+        let pebbles = line
             .split_whitespace()
-            .map(|s| s.parse().expect("a number"))
-            .collect();
+            .map(|s| {
+                s.parse()
+                    .map_err(|_| ParseError::at(1, &line, s, "a number"))
+            })
+            .collect::<Result<_, _>>()?;
 
-        Self {
+        Ok(Self {
             pebbles,
             cache: HashMap::new(),
-        }
+            blinks,
+        })
     }
 
     fn num_digits(n: u64) -> u32 {
@@ -96,15 +110,41 @@ impl PlutonianPebbles {
     }
 }
 
-pub fn run(input: Input, part: day::Part) -> Result<i64> {
-    let mut pebbles = PlutonianPebbles::new(input);
+pub struct Day;
+
+impl Solution for Day {
+    type Model = PlutonianPebbles;
+
+    const DAY: u8 = 11;
+    const TITLE: &'static str = "Plutonian Pebbles";
+    const PARAMS: &'static [&'static dyn ParamSpec] = &[&BLINKS_ONE, &BLINKS_TWO];
+
+    fn parse(input: Input, params: &Params) -> Result<Self::Model> {
+        let blinks = (params.get(&BLINKS_ONE)?, params.get(&BLINKS_TWO)?);
+        PlutonianPebbles::new(input, blinks)
+    }
+
+    fn part_one(pebbles: &Self::Model) -> Result<Answer> {
+        let mut pebbles = pebbles.clone();
+
+        Ok(pebbles.count_pebbles_after_blinks(pebbles.blinks.0).into())
+    }
+
+    fn part_two(pebbles: &Self::Model) -> Result<Answer> {
+        let mut pebbles = pebbles.clone();
+
+        Ok(pebbles.count_pebbles_after_blinks(pebbles.blinks.1).into())
+    }
+}
 
-    let result = match part {
-        day::Part::One => pebbles.count_pebbles_after_blinks(25),
-        day::Part::Two => pebbles.count_pebbles_after_blinks(75),
-    } as i64;
+#[cfg(test)]
+const EXAMPLE: &str = "\
+125 17
+";
 
-    Ok(result)
+example_tests! {
+    part_one: part_one(EXAMPLE) => 55312,
+    part_one_six_blinks: part_one(EXAMPLE, blinks_one = 6) => 22,
 }
 
 day_tests!("day_11-1.dat", 216042, 255758646442399);
@@ -1,13 +1,14 @@
 use std::collections::HashMap;
 use std::fmt::{self};
 
+use crate::answer::Answer;
 use crate::error::Result;
 use crate::input::Input;
-use crate::util::{Dims, Vec2};
-use crate::{day, day_tests};
-
-#[allow(unused_imports)]
-use log::info;
+use crate::params::Params;
+use crate::solution::Solution;
+use crate::util::grid::Grid;
+use crate::util::Vec2;
+use crate::{day_tests, example_tests};
 
 #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
 enum FenceSide {
@@ -63,62 +64,39 @@ struct FenceGroupKey {
     axis: i64,
 }
 
-type GardenData = Vec<Vec<u8>>;
-type FencesData = Vec<Vec<u8>>;
+type GardenData = Grid<u8>;
+type FencesData = Grid<u8>;
 type FencePieces = Vec<FencePiece>;
 
 type FencePieceGroups = HashMap<FenceGroupKey, FencePieces>;
 
-struct GardenGroups {
+pub struct GardenGroups {
     data: GardenData,
     fences: FencesData,
-
-    dims: Dims,
 }
 
 impl GardenGroups {
     const BORDER_BYTE: u8 = b'_';
 
-    fn new(mut input: Input) -> Self {
-        let mut data = Vec::new();
-        data.push(vec![]); // this will be replaced with a horizontal border once we know the width
-
-        loop {
-            let mut buffer = vec![Self::BORDER_BYTE];
-            match input.read_line_as_bytes_into(&mut buffer) {
-                Some(()) => {
-                    let last_idx = buffer.len() - 1;
-                    buffer[last_idx] = Self::BORDER_BYTE;
-                    data.push(buffer);
-                }
-                None => {
-                    break;
-                }
-            }
-        }
+    fn new(mut input: Input) -> Result<Self> {
+        let (garden, _) = Grid::parse(&mut input, &[], |c| match c {
+            'A'..='Z' => Ok(c as u8),
+            _ => Err("a plant, a letter from A to Z"),
+        })?;
 
-        let width = data[data.len() - 1].len();
-        let horiz_border = vec![Self::BORDER_BYTE; width];
-
-        data[0] = horiz_border.clone();
-        data.push(horiz_border);
-
-        let height = data.len();
-
-        let dims = Dims { width, height };
+        // surround the garden with a border, so that every plot has four neighbours
+        let mut data = Grid::with_size(garden.width() + 2, garden.height() + 2, Self::BORDER_BYTE);
+        for (pos, &plant) in garden.iter() {
+            data[pos + Vec2::new(1, 1)] = plant;
+        }
 
-        let fences = Self::find_fence_counts(&data, dims);
+        let fences = Self::find_fence_counts(&data);
 
-        Self {
-            data,
-            fences,
-            dims: Dims { width, height },
-        }
+        Ok(Self { data, fences })
     }
 
-    fn alloc_data(dims: Dims) -> Vec<Vec<u8>> {
-        let Dims { width, height } = dims;
-        vec![vec![0; width]; height]
+    fn alloc_data(&self) -> Grid<u8> {
+        self.data.map(|_| 0)
     }
 
     fn at(&self, pos: Vec2) -> u8 {
@@ -127,23 +105,19 @@ impl GardenGroups {
 
     fn num_fences_at_pos(garden: &GardenData, pos: Vec2) -> usize {
         let label = garden[pos];
-        let fances = pos
-            .neighbours()
-            .into_iter()
-            .filter(|&p| garden[p] != label)
-            .count();
-        fances
-    }
 
-    fn find_fence_counts(data: &GardenData, dims: Dims) -> FencesData {
-        let Dims { width, height } = dims;
+        garden
+            .neighbours4(pos)
+            .filter(|&(_, &plant)| plant != label)
+            .count()
+    }
 
-        let mut fences = Self::alloc_data(dims);
+    fn find_fence_counts(data: &GardenData) -> FencesData {
+        let mut fences = data.map(|_| 0);
 
-        for y in 1..height - 1 {
-            for x in 1..width - 1 {
-                let fances = Self::num_fences_at_pos(data, (x, y).into());
-                fences[y][x] = fances as u8;
+        for y in 1..data.height() - 1 {
+            for x in 1..data.width() - 1 {
+                fences[(x, y)] = Self::num_fences_at_pos(data, Vec2::cell(x, y)) as u8;
             }
         }
 
@@ -152,7 +126,7 @@ impl GardenGroups {
 
     // techincally this is not a flood fill but plain old DFS... but hey, it started as with a
     // queue
-    fn flood_fill_from(&self, pos: Vec2, visited: &mut Vec<Vec<u8>>, mut func: impl FnMut(&Vec2)) {
+    fn flood_fill_from(&self, pos: Vec2, visited: &mut Grid<u8>, mut func: impl FnMut(&Vec2)) {
         let label = self.at(pos);
         let mut exploring = vec![pos];
 
@@ -165,8 +139,7 @@ impl GardenGroups {
 
             func(&pos);
 
-            for neighbour in pos.neighbours() {
-                let nlabel = self.at(neighbour);
+            for (neighbour, &nlabel) in self.data.neighbours4(pos) {
                 if visited[neighbour] == 0 && nlabel == label {
                     exploring.push(neighbour);
                 }
@@ -174,7 +147,7 @@ impl GardenGroups {
         }
     }
 
-    fn area_price(&self, pos: Vec2, visited: &mut Vec<Vec<u8>>) -> usize {
+    fn area_price(&self, pos: Vec2, visited: &mut Grid<u8>) -> usize {
         let mut area = 0;
         let mut fences = 0;
 
@@ -187,14 +160,14 @@ impl GardenGroups {
     }
 
     fn total_fences_price(&self) -> usize {
-        let mut visited = Self::alloc_data(self.dims);
+        let mut visited = self.alloc_data();
 
         let mut total_price = 0;
 
-        for y in 1..self.data.len() - 1 {
-            for x in 1..self.data[y].len() - 1 {
-                if visited[y][x] == 0 {
-                    let price = self.area_price((x, y).into(), &mut visited);
+        for y in 1..self.data.height() - 1 {
+            for x in 1..self.data.width() - 1 {
+                if visited[(x, y)] == 0 {
+                    let price = self.area_price(Vec2::cell(x, y), &mut visited);
                     total_price += price;
                 }
             }
@@ -206,14 +179,13 @@ impl GardenGroups {
     fn fences_at_pos(&self, pos: Vec2, fences: &mut FencePieces) {
         let label = self.at(pos);
 
-        for neighbour in pos.neighbours() {
-            let nlabel = self.at(neighbour);
+        for (neighbour, &nlabel) in self.data.neighbours4(pos) {
             if nlabel == label {
                 continue;
             }
 
-            let (x, y) = pos.into();
-            let (nx, ny) = neighbour.into();
+            let Vec2 { x, y } = pos;
+            let Vec2 { x: nx, y: ny } = neighbour;
 
             if nx == x {
                 if ny < y {
@@ -231,7 +203,7 @@ impl GardenGroups {
         }
     }
 
-    fn fences_in_region(&self, pos: Vec2, visited: &mut Vec<Vec<u8>>) -> (usize, FencePieces) {
+    fn fences_in_region(&self, pos: Vec2, visited: &mut Grid<u8>) -> (usize, FencePieces) {
         let mut fences = Vec::new();
         let mut area = 0;
 
@@ -281,7 +253,7 @@ impl GardenGroups {
                 },
             };
 
-            let group = groups.entry(key).or_insert_with(Vec::new);
+            let group = groups.entry(key).or_default();
 
             group.push(fence.clone());
         }
@@ -290,14 +262,14 @@ impl GardenGroups {
     }
 
     fn total_fence_sides(&self) -> usize {
-        let mut visited = Self::alloc_data(self.dims);
+        let mut visited = self.alloc_data();
 
         let mut total_price = 0;
 
-        for y in 1..self.data.len() - 1 {
-            for x in 1..self.data[y].len() - 1 {
-                if visited[y][x] == 0 {
-                    let (area, fences) = self.fences_in_region((x, y).into(), &mut visited);
+        for y in 1..self.data.height() - 1 {
+            for x in 1..self.data.width() - 1 {
+                if visited[(x, y)] == 0 {
+                    let (area, fences) = self.fences_in_region(Vec2::cell(x, y), &mut visited);
                     let num_sides = Self::analyze_sides(&fences);
 
                     total_price += num_sides * area;
@@ -309,16 +281,44 @@ impl GardenGroups {
     }
 }
 
-#[allow(unreachable_code, unused_variables)]
-pub fn run(input: Input, part: day::Part) -> Result<i64> {
-    let gardens = GardenGroups::new(input);
+pub struct Day;
+
+impl Solution for Day {
+    type Model = GardenGroups;
+
+    const DAY: u8 = 12;
+    const TITLE: &'static str = "Garden Groups";
+
+    fn parse(input: Input, _: &Params) -> Result<Self::Model> {
+        GardenGroups::new(input)
+    }
+
+    fn part_one(gardens: &Self::Model) -> Result<Answer> {
+        Ok(gardens.total_fences_price().into())
+    }
 
-    let result = match part {
-        day::Part::One => gardens.total_fences_price(),
-        day::Part::Two => gardens.total_fence_sides(),
-    } as i64;
+    fn part_two(gardens: &Self::Model) -> Result<Answer> {
+        Ok(gardens.total_fence_sides().into())
+    }
+}
 
-    Ok(result)
+#[cfg(test)]
+const EXAMPLE: &str = "\
+RRRRIICCFF
+RRRRIICCCF
+VVRRRCCFFF
+VVRCCCJFFF
+VVVVCJJCFE
+VVIVCCJJEE
+VVIIICJJEE
+MIIIIIJJEE
+MIIISIJEEE
+MMMISSJEEE
+";
+
+example_tests! {
+    part_one: part_one(EXAMPLE) => 1930,
+    part_two: part_two(EXAMPLE) => 1206,
 }
 
 day_tests!("day_12-1.dat", 1549354, 937032);
@@ -1,74 +1,45 @@
+use crate::answer::Answer;
 use crate::error::Result;
 use crate::input::Input;
+use crate::params::Params;
+use crate::solution::Solution;
 use crate::util::math::checked_int_div;
+use crate::util::parse::{self, Line};
 use crate::util::Vec2;
-use crate::{day, day_tests};
+use crate::{day_tests, example_tests};
 
-#[allow(unused_imports)]
-use log::info;
+use log::trace;
 
 struct ClawMachinesReader {
     input: Input,
-    eof: bool,
 }
 
 impl ClawMachinesReader {
     fn new(input: Input) -> ClawMachinesReader {
-        ClawMachinesReader { input, eof: false }
+        ClawMachinesReader { input }
     }
 }
 
 impl ClawMachinesReader {
-    fn read_vec2(&mut self, is_button: bool) -> Option<Vec2> {
-        let parts = self
-            .input
-            .read_line()
-            .expect("button input")
-            .split(':') // -> Button N: X+m, Y=+n
-            .skip(1) // skip the label
-            .take(1)
-            .map(|s| {
-                s.trim()
-                    .split(", ") // -> "X+m", "Y=+n"
-                    .map(|s| {
-                        s.to_string()
-                            .split(if is_button { '+' } else { '=' })
-                            .skip(1) // skip the X or Y
-                            .next()
-                            .expect("offset or position")
-                            .parse::<i64>()
-                            .expect("a number")
-                    })
-                    .collect::<Vec<_>>()
-            })
-            .next()
-            .expect("valid input");
-
-        if let &[x, y] = parts.as_slice() {
-            Some(Vec2 { x, y })
-        } else {
-            None
-        }
+    fn read_vec2(line: &Line, label: &str) -> Result<Vec2> {
+        Ok(line.vec2(line.value(label)?)?)
     }
-}
-
-impl std::iter::Iterator for ClawMachinesReader {
-    type Item = ClawMachine;
-
-    fn next(&mut self) -> Option<ClawMachine> {
-        if self.eof {
-            return None;
-        }
 
-        let button_a = self.read_vec2(true).expect("A button input");
-        let button_b = self.read_vec2(true).expect("B button input");
-        let prize = self.read_vec2(false).expect("prize input");
+    fn read_claw_machine(section: &[Line]) -> Result<ClawMachine> {
+        let [button_a, button_b, prize] = section else {
+            let last = section.last().expect("sections are never empty");
+            return Err(last
+                .line_error("a claw machine of Button A, Button B and Prize")
+                .into());
+        };
 
-        println!("A: {:?}, B: {:?}, P: {:?}", button_a, button_b, prize);
+        let button_a = Self::read_vec2(button_a, "Button A")?;
+        let button_b = Self::read_vec2(button_b, "Button B")?;
+        let prize = Self::read_vec2(prize, "Prize")?;
 
-        self.eof = self.input.read_line().is_none(); // consume the empty line and mark the end
+        trace!(target: "day_13::trace", "A: {:?}, B: {:?}, P: {:?}", button_a, button_b, prize);
 
-        Some(ClawMachine {
+        Ok(ClawMachine {
             button_a,
             button_b,
             prize,
@@ -76,6 +47,14 @@ impl std::iter::Iterator for ClawMachinesReader {
     }
 }
 
+impl std::iter::Iterator for ClawMachinesReader {
+    type Item = Result<ClawMachine>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        parse::read_section(&mut self.input).map(|section| Self::read_claw_machine(&section))
+    }
+}
+
 struct ClawMachine {
     button_a: Vec2,
     button_b: Vec2,
@@ -124,15 +103,15 @@ impl ClawMachine {
     }
 }
 
-struct Arcade {
+pub struct Arcade {
     claw_machines: Vec<ClawMachine>,
 }
 
 impl Arcade {
-    fn new(input: Input) -> Arcade {
-        Arcade {
-            claw_machines: ClawMachinesReader::new(input).collect(),
-        }
+    fn new(input: Input) -> Result<Arcade> {
+        Ok(Arcade {
+            claw_machines: ClawMachinesReader::new(input).collect::<Result<_>>()?,
+        })
     }
 
     fn solve_with_offset(&self, offset: &Vec2) -> i64 {
@@ -149,24 +128,57 @@ impl Arcade {
     }
 
     fn solve(&self) -> i64 {
-        self.solve_with_offset(&(0, 0).into())
+        self.solve_with_offset(&Vec2::new(0, 0))
     }
 
     fn solve_large(&self) -> i64 {
-        let offset = 10000000000000_usize;
-        self.solve_with_offset(&(offset, offset).into())
+        let offset = 10000000000000;
+        self.solve_with_offset(&Vec2::new(offset, offset))
     }
 }
 
-pub fn run(input: Input, part: day::Part) -> Result<i64> {
-    let arcade = Arcade::new(input);
+pub struct Day;
+
+impl Solution for Day {
+    type Model = Arcade;
+
+    const DAY: u8 = 13;
+    const TITLE: &'static str = "Claw Contraption";
+
+    fn parse(input: Input, _: &Params) -> Result<Self::Model> {
+        Arcade::new(input)
+    }
+
+    fn part_one(arcade: &Self::Model) -> Result<Answer> {
+        Ok(arcade.solve().into())
+    }
+
+    fn part_two(arcade: &Self::Model) -> Result<Answer> {
+        Ok(arcade.solve_large().into())
+    }
+}
+
+#[cfg(test)]
+const EXAMPLE: &str = "\
+Button A: X+94, Y+34
+Button B: X+22, Y+67
+Prize: X=8400, Y=5400
+
+Button A: X+26, Y+66
+Button B: X+67, Y+21
+Prize: X=12748, Y=12176
+
+Button A: X+17, Y+86
+Button B: X+84, Y+37
+Prize: X=7870, Y=6450
 
-    let result = match part {
-        day::Part::One => arcade.solve(),
-        day::Part::Two => arcade.solve_large(),
-    } as i64;
+Button A: X+69, Y+23
+Button B: X+27, Y+71
+Prize: X=18641, Y=10279
+";
 
-    Ok(result)
+example_tests! {
+    part_one: part_one(EXAMPLE) => 480,
 }
 
 day_tests!("day_13-1.dat", 32026, 89013607072065);
@@ -1,10 +1,21 @@
+use crate::answer::Answer;
 use crate::error::{Error, Result};
 use crate::input::Input;
+use crate::params::{Param, ParamSpec, Params};
+use crate::solution::Solution;
+use crate::util::budget::{self, Budget};
+use crate::util::grid::Grid;
+use crate::util::parse;
+use crate::util::record::Recorder;
+use crate::util::render::{self, Glyph, Renderer, Rgb};
 use crate::util::Vec2;
-use crate::{day, day_tests};
+use crate::{day_tests, example_tests};
 
-use std::str::FromStr;
+const WIDTH: Param<i64> = Param::new("width", 101).at_least(1);
+const HEIGHT: Param<i64> = Param::new("height", 103).at_least(1);
+const ITERATIONS: Param<u64> = Param::new("iterations", 1000000);
 
+#[derive(Clone)]
 struct Robot {
     p: Vec2,
     v: Vec2,
@@ -22,35 +33,40 @@ struct RobotInputReader {
 }
 
 impl Iterator for RobotInputReader {
-    type Item = Robot;
+    type Item = Result<Robot>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let line = self.input.read_line()?;
+        let line = parse::read_line(&mut self.input)?;
 
-        let parts = line.split_whitespace();
+        let read_robot = || -> Result<Robot> {
+            let (p, v) = line.split_once(" ", "p=x,y v=x,y")?;
 
-        let mut p_and_v = parts
-            .take(2)
-            .map(|s| s.split('=').nth(1).map(Vec2::from_str)?.ok());
-
-        let p = p_and_v.next().flatten().expect("valid position");
-        let v = p_and_v.next().flatten().expect("valid velocity");
+            Ok(Robot {
+                p: line.vec2(line.strip_prefix(p, "p=")?)?,
+                v: line.vec2(line.strip_prefix(v, "v=")?)?,
+            })
+        };
 
-        Some(Robot { p, v })
+        Some(read_robot())
     }
 }
 
-struct EbHq {
+#[derive(Clone)]
+pub struct EbHq {
     robots: Vec<Robot>,
     bounds: Vec2,
+    easteregg_iterations: u64,
+    budget: Budget,
 }
 
 impl EbHq {
-    fn new(input: Input) -> Self {
-        Self {
-            robots: RobotInputReader { input }.collect(),
-            bounds: Vec2::new(101, 103),
-        }
+    fn new(input: Input, bounds: Vec2, easteregg_iterations: u64, budget: Budget) -> Result<Self> {
+        Ok(Self {
+            robots: RobotInputReader { input }.collect::<Result<_>>()?,
+            bounds,
+            easteregg_iterations,
+            budget,
+        })
     }
 
     fn qdrant(&self, p: &Vec2) -> Option<u8> {
@@ -69,7 +85,7 @@ impl EbHq {
     fn qdrant_score_after_n_seconds(&self, seconds: i64) -> u64 {
         self.robots
             .iter()
-            .map(|robot| &(robot.p + &(&robot.v * seconds)) % &self.bounds)
+            .map(|robot| (robot.p + robot.v * seconds) % self.bounds)
             .map(|p| self.qdrant(&p))
             .fold(vec![0_u64; 4], |mut acc, q| {
                 if let Some(q) = q {
@@ -81,18 +97,27 @@ impl EbHq {
             .product::<u64>()
     }
 
-    fn print_if_match(&self, positions: &Vec<Vec2>, pattern: &[u8]) -> bool {
-        let canvas = positions.iter().fold(
-            vec![vec![b'.'; self.bounds.x as usize]; self.bounds.y as usize],
-            |mut acc, p| {
-                acc[p.y as usize][p.x as usize] = b'#';
+    fn draw(&self, positions: &[Vec2]) -> Grid<u8> {
+        positions.iter().fold(
+            Grid::with_size(self.bounds.x as usize, self.bounds.y as usize, b'.'),
+            |mut acc, &p| {
+                acc[p] = b'#';
                 acc
             },
-        );
+        )
+    }
+
+    fn glyph(c: &u8) -> Glyph {
+        match c {
+            b'#' => Glyph::colored('#', Rgb::GREEN),
+            _ => Glyph::new('.'),
+        }
+    }
 
+    fn log_if_match(&self, canvas: &Grid<u8>, pattern: &[u8]) -> bool {
         let mut render = false;
 
-        'outer: for row in &canvas {
+        'outer: for row in canvas.rows() {
             for chunk in row.chunks(pattern.len()) {
                 if chunk == pattern {
                     render = true;
@@ -105,8 +130,10 @@ impl EbHq {
             return false;
         }
 
-        for row in canvas {
-            println!("{}", row.iter().map(|&c| c as char).collect::<String>());
+        if render::enabled("day_14::render") {
+            let renderer = Renderer::new(canvas, Self::glyph);
+
+            render::show("day_14::render", "day_14-easteregg", &renderer);
         }
 
         true
@@ -120,35 +147,100 @@ impl EbHq {
     // Task #2 - look for a pattern that could be a Christmas tree
     fn simulate(&mut self, iterations: u64) -> Result<u64> {
         let pattern = "#######".as_bytes();
+        let mut recorder = Recorder::from_env("day_14::render", "day_14-robots");
+        let mut meter = self.budget.start("simulate");
+        let mut result = Err(Error::NoSolution(format!(
+            "No Christmas tree found after {iterations} iterations",
+        )));
 
         for i in 0..iterations {
+            if let Err(e) = meter.step() {
+                result = Err(e);
+                break;
+            }
+
             let positions = self
                 .robots
                 .iter_mut()
                 .map(|robot| robot.step(&self.bounds))
                 .collect::<Vec<_>>();
+            let canvas = self.draw(&positions);
 
-            if self.print_if_match(&positions, pattern) {
-                return Ok(i + 1);
+            if let Some(recorder) = &mut recorder {
+                if recorder.tick() {
+                    recorder.push(&Renderer::new(&canvas, Self::glyph));
+                }
+            }
+
+            if self.log_if_match(&canvas, pattern) {
+                result = Ok(i + 1);
+                break;
             }
         }
 
-        Err(Error::NoSolution(format!(
-            "No Christmas tree found after {iterations} iterations",
-        )))
+        if let Some(recorder) = recorder {
+            recorder.save();
+        }
+
+        result
     }
 }
 
-pub fn run(input: Input, part: day::Part) -> Result<i64> {
-    let mut ebhq = EbHq::new(input);
-    let easteregg_iterations = 1000000;
+pub struct Day;
+
+impl Solution for Day {
+    type Model = EbHq;
+
+    const DAY: u8 = 14;
+    const TITLE: &'static str = "Restroom Redoubt";
+    const PARAMS: &'static [&'static dyn ParamSpec] = &[
+        &WIDTH,
+        &HEIGHT,
+        &ITERATIONS,
+        &budget::TIMEOUT,
+        &budget::MAX_STEPS,
+    ];
+
+    fn parse(input: Input, params: &Params) -> Result<Self::Model> {
+        let bounds = Vec2::new(params.get(&WIDTH)?, params.get(&HEIGHT)?);
+        EbHq::new(
+            input,
+            bounds,
+            params.get(&ITERATIONS)?,
+            Budget::from_params(params)?,
+        )
+    }
 
-    let result = match part {
-        day::Part::One => ebhq.qdrant_score(),
-        day::Part::Two => ebhq.simulate(easteregg_iterations)?,
-    } as i64;
+    fn part_one(ebhq: &Self::Model) -> Result<Answer> {
+        Ok(ebhq.qdrant_score().into())
+    }
+
+    fn part_two(ebhq: &Self::Model) -> Result<Answer> {
+        let mut ebhq = ebhq.clone();
+        let iterations = ebhq.easteregg_iterations;
+
+        Ok(ebhq.simulate(iterations)?.into())
+    }
+}
 
-    Ok(result)
+#[cfg(test)]
+const EXAMPLE: &str = "\
+p=0,4 v=3,-3
+p=6,3 v=-1,-3
+p=10,3 v=-1,2
+p=2,0 v=2,-1
+p=0,0 v=1,3
+p=3,0 v=-2,-2
+p=7,6 v=-1,-3
+p=3,0 v=-1,-2
+p=9,3 v=2,3
+p=7,3 v=-1,2
+p=2,4 v=2,-3
+p=9,5 v=-3,-3
+";
+
+example_tests! {
+    part_one: part_one(EXAMPLE, width = 11, height = 7) => 12,
 }
 
 day_tests!("day_14-1.dat", 211773366, 7344);
@@ -1,54 +1,17 @@
-use crate::error::Result;
+use crate::answer::Answer;
+use crate::error::{ParseError, Result};
 use crate::input::Input;
-use crate::util::Vec2;
-use crate::{day, day_tests};
+use crate::params::Params;
+use crate::solution::Solution;
+use crate::util::grid::Grid;
+use crate::util::record::Recorder;
+use crate::util::render::{self, Glyph, Renderer, Rgb};
+use crate::util::{Dir4, Vec2};
+use crate::{day_tests, example_tests};
 
 use std::collections::HashMap;
-use std::fmt::{self, Display};
-use std::io::Write;
-use std::time::Duration;
 
-#[derive(Debug, PartialEq, Copy, Clone)]
-enum Dir {
-    Left,
-    Right,
-    Up,
-    Down,
-}
-
-impl Dir {
-    fn as_vec(&self) -> Vec2 {
-        match self {
-            Dir::Left => Vec2::new(-1, 0),
-            Dir::Right => Vec2::new(1, 0),
-            Dir::Up => Vec2::new(0, -1),
-            Dir::Down => Vec2::new(0, 1),
-        }
-    }
-}
-
-impl From<char> for Dir {
-    fn from(c: char) -> Self {
-        match c {
-            '<' => Dir::Left,
-            '>' => Dir::Right,
-            '^' => Dir::Up,
-            'v' => Dir::Down,
-            _ => unreachable!(),
-        }
-    }
-}
-
-impl Display for Dir {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            Dir::Left => write!(f, "<"),
-            Dir::Right => write!(f, ">"),
-            Dir::Up => write!(f, "^"),
-            Dir::Down => write!(f, "v"),
-        }
-    }
-}
+use log::{log_enabled, Level};
 
 #[derive(Debug, PartialEq, Copy, Clone)]
 enum Tile {
@@ -59,54 +22,34 @@ enum Tile {
     LargeBoxR(u64),
 }
 
-enum Highlight {
-    None,
-    Red,
-    Blue,
-}
-
 impl Tile {
-    fn print(&self, highlight: Highlight) {
+    fn glyph(&self) -> Glyph {
         match self {
-            Tile::LargeBoxL(_) | Tile::LargeBoxR(_) => match highlight {
-                Highlight::Red => print!("\x1B[1;31m{}\x1B[0m", self),
-                Highlight::Blue => print!("\x1B[1;34m{}\x1B[0m", self),
-                _ => print!("{}", self),
-            },
-            _ => print!("{}", self),
-        }
-    }
-}
-
-// impl display for tile
-impl Display for Tile {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            Tile::Empty => write!(f, " "),
-            Tile::Wall => write!(f, "▓"),
-            Tile::Box(_) => write!(f, "☐"),
-            Tile::LargeBoxL(_) => write!(f, "╟"),
-            Tile::LargeBoxR(_) => write!(f, "╢"),
+            Tile::Empty => Glyph::new(' '),
+            Tile::Wall => Glyph::new('▓'),
+            Tile::Box(_) => Glyph::colored('☐', Rgb::WHITE),
+            Tile::LargeBoxL(_) => Glyph::colored('╟', Rgb::WHITE),
+            Tile::LargeBoxR(_) => Glyph::colored('╢', Rgb::WHITE),
         }
     }
 }
 
 impl Tile {
-    fn from_char(c: char, box_id: &mut u64) -> Self {
+    fn from_char(c: char, box_id: &mut u64) -> Option<Self> {
         match c {
-            '.' => Tile::Empty,
-            '#' => Tile::Wall,
+            '.' | '@' => Some(Tile::Empty),
+            '#' => Some(Tile::Wall),
             'O' => {
                 *box_id += 1;
-                Tile::Box(*box_id)
+                Some(Tile::Box(*box_id))
             }
-            _ => Tile::Empty,
+            _ => None,
         }
     }
 }
 
-type Map = Vec<Vec<Tile>>;
-type Moves = Vec<Dir>;
+type Map = Grid<Tile>;
+type Moves = Vec<Dir4>;
 
 type MoveSet = HashMap<Vec2, Tile>;
 
@@ -118,52 +61,39 @@ impl WarehouseInputReader {
         Self { input }
     }
 
-    fn read_map(&mut self) -> (Vec2, Map) {
+    fn read_map(&mut self) -> Result<(Vec2, Map)> {
         let mut box_id = 0;
-        let mut y = 0;
-        let mut start = Vec2::new(0, 0);
 
-        let mut map = vec![];
+        let (map, markers) = Grid::parse(&mut self.input, &['@'], |c| {
+            Tile::from_char(c, &mut box_id).ok_or("a map tile ., #, O or @")
+        })?;
 
-        while let Some(line) = self.input.read_line() {
-            let line = line.trim();
-            if line.is_empty() {
-                break;
-            }
+        Ok((markers.single('@')?, map))
+    }
 
-            let row = line
-                .char_indices()
-                .map(|(x, c)| {
-                    if (c) == '@' {
-                        start = Vec2::from((x, y));
-                    }
-                    Tile::from_char(c, &mut box_id)
-                })
-                .collect::<Vec<_>>();
+    fn read_moves(mut self) -> Result<Moves> {
+        let mut moves = Vec::new();
 
-            map.push(row);
+        while let Some(line) = self.input.read_line() {
+            let line = line.trim_end();
+            let line_no = self.input.line_number();
 
-            y += 1;
-        }
+            for (x, c) in line.char_indices() {
+                let dir = Dir4::from_arrow(c).ok_or_else(|| {
+                    let dir = &line[x..x + c.len_utf8()];
+                    ParseError::at(line_no, line, dir, "a move <, >, ^ or v")
+                })?;
 
-        (start, map)
-    }
+                moves.push(dir);
+            }
+        }
 
-    fn read_moves(self) -> Moves {
-        self.input
-            .lines()
-            .map(|line| {
-                line.expect("valid line input")
-                    .chars()
-                    .map(Dir::from)
-                    .collect::<Vec<_>>()
-            })
-            .flatten()
-            .collect::<Vec<_>>()
+        Ok(moves)
     }
 }
 
-struct Warehouse {
+#[derive(Clone)]
+pub struct Warehouse {
     start: Vec2,
     map: Map,
     moves: Moves,
@@ -173,71 +103,57 @@ struct Warehouse {
 }
 
 impl Warehouse {
-    fn new(input: Input) -> Warehouse {
+    fn new(input: Input) -> Result<Warehouse> {
         let mut reader = WarehouseInputReader::new(input);
 
-        let (start, map) = reader.read_map();
-        let moves = reader.read_moves();
+        let (start, map) = reader.read_map()?;
+        let moves = reader.read_moves()?;
 
-        Warehouse {
+        Ok(Warehouse {
             start,
             map,
             moves,
             current_move_set: MoveSet::new(),
             can_move: true,
-        }
+        })
     }
 
     fn inflate(self) -> Self {
         let inflatd_map = self
             .map
-            .iter()
+            .rows()
             .map(|row| {
                 row.iter()
-                    .map(|tile| match tile {
+                    .flat_map(|tile| match tile {
                         Tile::Empty => [Tile::Empty, Tile::Empty],
                         Tile::Wall => [Tile::Wall, Tile::Wall],
 
                         Tile::Box(id) => [Tile::LargeBoxL(*id), Tile::LargeBoxR(*id)],
                         _ => unreachable!(),
                     })
-                    .flatten()
                     .collect::<Vec<Tile>>()
             })
             .collect::<Vec<_>>();
 
         Warehouse {
             start: Vec2::new(self.start.x * 2, self.start.y),
-            map: inflatd_map,
+            map: Grid::new(inflatd_map),
             moves: self.moves,
             current_move_set: MoveSet::new(),
             can_move: true,
         }
     }
 
-    fn render_map(&self, robot_at: Vec2) {
-        print!("\x1B[2J\x1B[1;1H");
+    /// Renders the map with the robot and the boxes it tried to move last, blue if they moved
+    /// and red if they were blocked.
+    fn render_map(&self, robot_at: Vec2, emit: impl FnOnce(&Renderer<Tile>)) {
+        let moved = if self.can_move { Rgb::BLUE } else { Rgb::RED };
 
-        for (y, row) in self.map.iter().enumerate() {
-            for (x, tile) in row.iter().enumerate() {
-                if Vec2::from((x, y)) == robot_at {
-                    print!("☺");
-                    continue;
-                }
-                if self.current_move_set.contains_key(&Vec2::from((x, y))) {
-                    if self.can_move {
-                        tile.print(Highlight::Blue);
-                    } else {
-                        tile.print(Highlight::Red);
-                    }
-                } else {
-                    tile.print(Highlight::None);
-                }
-            }
-            println!();
-        }
-        std::io::stdout().flush().unwrap();
-        std::thread::sleep(Duration::from_millis(16));
+        let renderer = Renderer::new(&self.map, Tile::glyph)
+            .highlight(self.current_move_set.keys().copied(), moved)
+            .cursor(robot_at, Glyph::colored('☺', Rgb::YELLOW));
+
+        emit(&renderer);
     }
 
     fn can_move_selected(&self, dir: &Vec2) -> bool {
@@ -257,7 +173,7 @@ impl Warehouse {
     }
 
     fn move_selected(&mut self, dir: &Vec2) {
-        for (pos, _tile) in &self.current_move_set {
+        for pos in self.current_move_set.keys() {
             self.map[*pos] = Tile::Empty;
         }
 
@@ -272,9 +188,9 @@ impl Warehouse {
         self.current_move_set = new_current_move_set;
     }
 
-    fn step(&mut self, from: Vec2, dir: Dir) -> Vec2 {
-        let dir = dir.as_vec();
-        let to = from + &dir;
+    fn step(&mut self, from: Vec2, dir: Dir4) -> Vec2 {
+        let dir = dir.delta();
+        let to = from + dir;
         let tile = self.map[to];
 
         match tile {
@@ -305,10 +221,10 @@ impl Warehouse {
     }
 
     fn find_empty_in_dir(&self, from: &Vec2, dir: &Vec2) -> Option<Vec2> {
-        let width = self.map[0].len();
+        let width = self.map.width();
 
         for i in 1..width {
-            let pos = from + &(dir * i as i64);
+            let pos = from + dir * i as i64;
             match self.map[pos] {
                 Tile::Empty => return Some(pos),
                 Tile::Wall => return None,
@@ -360,27 +276,23 @@ impl Warehouse {
     }
 
     fn swap_tiles(&mut self, a: Vec2, b: Vec2) {
-        let aux = self.map[a];
-        self.map[a] = self.map[b];
-        self.map[b] = aux;
+        let tile = std::mem::replace(&mut self.map[a], Tile::Empty);
+        self.map[a] = std::mem::replace(&mut self.map[b], tile);
     }
 
     fn gps(&self) -> usize {
-        let mut acc = 0;
-
-        for y in 0..self.map.len() {
-            for x in 0..self.map[y].len() {
-                match self.map[y][x] {
-                    Tile::Box(_) | Tile::LargeBoxL(_) => acc += 100 * y + x,
-                    _ => {}
-                }
-            }
-        }
-
-        acc
+        self.map
+            .iter()
+            .map(|(pos, tile)| match tile {
+                Tile::Box(_) | Tile::LargeBoxL(_) => (100 * pos.y + pos.x) as usize,
+                _ => 0,
+            })
+            .sum()
     }
 
-    fn replay_moves(&mut self, render: bool) -> usize {
+    /// Moves the robot around, renders and recordings of it are saved as `name`.
+    fn replay_moves(&mut self, name: &str) -> usize {
+        let mut recorder = Recorder::from_env("day_15::render", name);
         let mut pos = self.start;
 
         for mi in 0..self.moves.len() {
@@ -388,27 +300,101 @@ impl Warehouse {
 
             pos = self.step(pos, m);
 
-            if render {
-                self.render_map(pos);
+            if log_enabled!(target: "day_15::render", Level::Debug) {
+                self.render_map(pos, |renderer| {
+                    render::log("day_15::render", &format!("move {}", mi + 1), renderer)
+                });
+            }
+
+            if let Some(recorder) = &mut recorder {
+                if recorder.tick() {
+                    self.render_map(pos, |renderer| recorder.push(renderer));
+                }
             }
         }
 
+        if let Some(recorder) = recorder {
+            recorder.save();
+        }
+
+        if render::enabled("day_15::render") {
+            self.render_map(pos, |renderer| {
+                render::show("day_15::render", name, renderer)
+            });
+        }
+
         self.gps()
     }
 }
 
-pub fn run(input: Input, part: day::Part) -> Result<i64> {
-    let mut warehouse = Warehouse::new(input);
+pub struct Day;
 
-    let result = match part {
-        day::Part::One => warehouse.replay_moves(false),
-        day::Part::Two => {
-            let mut warehouse = warehouse.inflate();
-            warehouse.replay_moves(false)
-        }
-    } as i64;
+impl Solution for Day {
+    type Model = Warehouse;
+
+    const DAY: u8 = 15;
+    const TITLE: &'static str = "Warehouse Woes";
+
+    fn parse(input: Input, _: &Params) -> Result<Self::Model> {
+        Warehouse::new(input)
+    }
+
+    fn part_one(warehouse: &Self::Model) -> Result<Answer> {
+        let mut warehouse = warehouse.clone();
+
+        Ok(warehouse.replay_moves("day_15-warehouse").into())
+    }
+
+    fn part_two(warehouse: &Self::Model) -> Result<Answer> {
+        let mut warehouse = warehouse.clone().inflate();
+
+        Ok(warehouse.replay_moves("day_15-large-warehouse").into())
+    }
+}
 
-    Ok(result)
+#[cfg(test)]
+const SMALL_EXAMPLE: &str = "\
+########
+#..O.O.#
+##@.O..#
+#...O..#
+#.#.O..#
+#...O..#
+#......#
+########
+
+<^^>>>vv<v>>v<<
+";
+
+#[cfg(test)]
+const EXAMPLE: &str = "\
+##########
+#..O..O.O#
+#......O.#
+#.OO..O.O#
+#..O@..O.#
+#O#..O...#
+#O..O..O.#
+#.OO.O.OO#
+#....O...#
+##########
+
+<vv>^<v^>v>^vv^v>v<>v^v<v<^vv<<<^><<><>>v<vvv<>^v^>^<<<><<v<<<v^vv^v>^
+vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
+><>vv>v^v^<>><>>>><^^>vv>v<^^^>>v^v^<^^>v^^>v^<^v>v<>>v^v^<v>v^^<^^vv<
+<<v<^>>^^^^>>>v^<>vvv^><v<<<>^^^vv^<vvv>^>v<^^^^v<>^>vvvv><>>v^<<^^^^^
+^><^><>>><>^^<<^^v>>><^<v>^<vv>>v>>>^v><>^v><<<<v>>v<v<v>vvv>^<><<>^><
+^>><>^v<><^vvv<^^<><v<<<<<><^v<<<><<<^^<v<^^^><^>>^<v^><<<^>>^v<v^v<v^
+>^>>^v>vv>^<<^v<>><<><<v<<v><>v<^vv<<<>^^v^>^^>>><<^v>>v^v><^^>>^<>vv^
+<><^^>^^^<><vvvvv^v<v<<>^v<v>v<<^><<><<><<<^^<<<^<<>><<><^^^>^^<>^>v<>
+^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
+v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^
+";
+
+example_tests! {
+    part_one_small: part_one(SMALL_EXAMPLE) => 2028,
+    part_one: part_one(EXAMPLE) => 10092,
+    part_two: part_two(EXAMPLE) => 9021,
 }
 
 day_tests!("day_15-1.dat", 1495147, 1524905);
@@ -1,71 +1,22 @@
 use std::collections::HashSet;
 
-use crate::error::Result;
+use crate::answer::Answer;
+use crate::error::{Error, Result};
 use crate::input::Input;
-use crate::util::Vec2;
-use crate::{day, day_tests};
-
+use crate::params::Params;
+use crate::solution::Solution;
+use crate::util::grid::Grid;
+use crate::util::render::{self, Glyph, Renderer, Rgb};
+use crate::util::{Dir4, Vec2};
+use crate::{day_tests, example_tests};
+
+#[derive(Clone, Copy)]
 enum Tile {
     Wall,
     Open,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Dir {
-    East,
-    West,
-    North,
-    South,
-}
-
-impl Dir {
-    fn opposite(&self) -> Self {
-        match self {
-            Dir::East => Dir::West,
-            Dir::West => Dir::East,
-            Dir::North => Dir::South,
-            Dir::South => Dir::North,
-        }
-    }
-
-    fn as_vec(&self) -> Vec2 {
-        match self {
-            Dir::East => Vec2::new(1, 0),
-            Dir::West => Vec2::new(-1, 0),
-            Dir::North => Vec2::new(0, -1),
-            Dir::South => Vec2::new(0, 1),
-        }
-    }
-}
-
-// impl from usize
-impl From<usize> for Dir {
-    fn from(i: usize) -> Self {
-        match i {
-            0 => Dir::East,
-            1 => Dir::West,
-            2 => Dir::North,
-            3 => Dir::South,
-            _ => panic!("Invalid direction index: {}", i),
-        }
-    }
-}
-
-impl<T> std::ops::Index<Dir> for [T; 4] {
-    type Output = T;
-
-    fn index(&self, dir: Dir) -> &Self::Output {
-        &self[dir as usize]
-    }
-}
-
-impl<T> std::ops::IndexMut<Dir> for [T; 4] {
-    fn index_mut(&mut self, dir: Dir) -> &mut Self::Output {
-        &mut self[dir as usize]
-    }
-}
-
-type Maze = Vec<Vec<Tile>>;
+type Maze = Grid<Tile>;
 
 struct MazeInputReader {
     input: Input,
@@ -76,46 +27,18 @@ impl MazeInputReader {
         Self { input }
     }
 
-    fn read(&mut self) -> (Maze, Vec2, Vec2) {
-        let mut y = 0;
-        let mut start = Vec2::new(0, 0);
-        let mut end = Vec2::new(0, 0);
-
-        let mut maze = vec![];
-
-        while let Some(line) = self.input.read_line() {
-            let line = line.trim();
-            if line.is_empty() {
-                break;
-            }
-
-            let row = line
-                .char_indices()
-                .map(|(x, c)| match c {
-                    '#' => Tile::Wall,
-                    '.' => Tile::Open,
-                    'S' => {
-                        start = (x, y).into();
-                        Tile::Open
-                    }
-                    'E' => {
-                        end = (x, y).into();
-                        Tile::Open
-                    }
-                    _ => panic!("Unknown maze tile: {}", c),
-                })
-                .collect::<Vec<_>>();
-
-            maze.push(row);
+    fn read(&mut self) -> Result<(Maze, Vec2, Vec2)> {
+        let (maze, markers) = Grid::parse(&mut self.input, &['S', 'E'], |c| match c {
+            '#' => Ok(Tile::Wall),
+            '.' | 'S' | 'E' => Ok(Tile::Open),
+            _ => Err("a maze tile #, ., S or E"),
+        })?;
 
-            y += 1;
-        }
-
-        (maze, start, end)
+        Ok((maze, markers.single('S')?, markers.single('E')?))
     }
 }
 
-type NodeRef = (Vec2, Dir);
+type NodeRef = (Vec2, Dir4);
 
 #[derive(Debug)]
 struct Node {
@@ -146,28 +69,21 @@ impl Cell {
         }
     }
 
-    fn cost(&self, dir: Dir) -> i64 {
+    fn cost(&self, dir: Dir4) -> i64 {
         self.nodes[dir].cost
     }
 }
 
 struct MazeSolver {
-    grid: Vec<Vec<Option<Cell>>>,
+    grid: Grid<Option<Cell>>,
 }
 
 impl MazeSolver {
     fn new(maze: &Maze) -> Self {
-        let grid = maze
-            .iter()
-            .map(|row| {
-                row.iter()
-                    .map(|tile| match tile {
-                        Tile::Wall => None,
-                        Tile::Open => Some(Cell::new()),
-                    })
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<_>>();
+        let grid = maze.map(|tile| match tile {
+            Tile::Wall => None,
+            Tile::Open => Some(Cell::new()),
+        });
 
         Self { grid }
     }
@@ -190,59 +106,62 @@ impl MazeSolver {
         }
     }
 
-    fn min_cost_node(&mut self) -> (Vec2, Dir, &mut Node) {
+    // the open node with the lowest cost, none once every node reachable is closed
+    fn min_cost_node(&mut self) -> Option<(Vec2, Dir4, &mut Node)> {
         let mut min_cost = i64::MAX;
         let mut pos = Vec2::new(0, 0);
-        let mut dir = Dir::East;
-
-        for y in 0..self.grid.len() {
-            for x in 0..self.grid[y].len() {
-                if let Some(cell) = &self.grid[y][x] {
-                    for (i, node) in cell.nodes.iter().enumerate() {
-                        if !node.closed && node.cost < min_cost {
-                            min_cost = node.cost;
-                            pos = Vec2::from((x, y));
-                            dir = Dir::from(i);
-                        }
+        let mut dir = Dir4::Right;
+
+        for (cell_pos, cell) in self.grid.iter() {
+            if let Some(cell) = cell {
+                for (i, node) in cell.nodes.iter().enumerate() {
+                    if !node.closed && node.cost < min_cost {
+                        min_cost = node.cost;
+                        pos = cell_pos;
+                        dir = Dir4::ALL[i];
                     }
                 }
             }
         }
 
+        if min_cost == i64::MAX {
+            return None;
+        }
+
         let cell = self.grid[pos].as_mut().unwrap();
         let node = &mut cell.nodes[dir];
 
-        (pos, dir, node)
+        Some((pos, dir, node))
     }
 
-    fn shortest_path(&mut self, start: Vec2, end: Vec2) -> i64 {
-        self.update_cost(&(start, Dir::East), None, 0);
+    fn shortest_path(&mut self, start: Vec2, end: Vec2) -> Option<i64> {
+        self.update_cost(&(start, Dir4::Right), None, 0);
 
         loop {
-            let (pos, node_dir, node) = self.min_cost_node();
+            let (pos, node_dir, node) = self.min_cost_node()?;
             let cost = node.cost;
 
             node.closed = true;
 
             if pos == end {
-                return cost;
+                return Some(cost);
             }
 
-            for dir in [Dir::East, Dir::West, Dir::North, Dir::South].iter() {
-                let next_pos = pos + dir.as_vec();
+            for dir in Dir4::ALL {
+                let next_pos = pos + dir.delta();
 
-                if let None = self.grid[next_pos] {
-                    // wall
+                if !matches!(self.grid.get(next_pos), Some(Some(_))) {
+                    // wall or outside of the maze
                     continue;
                 }
 
                 let next_cost = match dir {
-                    d if *d == node_dir => 1,
-                    d if *d == node_dir.opposite() => continue, // we came from there, turning back is always more expensive
+                    d if d == node_dir => 1,
+                    d if d == node_dir.opposite() => continue, // we came from there, turning back is always more expensive
                     _ => 1001,
                 } + cost;
 
-                self.update_cost(&(next_pos, *dir), Some((pos, node_dir)), next_cost);
+                self.update_cost(&(next_pos, dir), Some((pos, node_dir)), next_cost);
             }
         }
     }
@@ -303,80 +222,121 @@ impl MazeSolver {
     }
 }
 
-struct ReindeerMaze {
+pub struct ReindeerMaze {
     maze: Maze,
     start: Vec2,
     end: Vec2,
-    verbose: bool,
 }
 
 impl ReindeerMaze {
-    fn new(input: Input, verbose: bool) -> Self {
-        let (maze, start, end) = MazeInputReader::new(input).read();
-        Self {
-            maze,
-            start,
-            end,
-            verbose,
-        }
+    fn new(input: Input) -> Result<Self> {
+        let (maze, start, end) = MazeInputReader::new(input).read()?;
+        Ok(Self { maze, start, end })
     }
 
-    fn print(&self, path: &[Vec2]) {
-        for (y, row) in self.maze.iter().enumerate() {
-            for (x, tile) in row.iter().enumerate() {
-                let pos = Vec2::from((x, y));
-                let c = if pos == self.start {
-                    'S'
-                } else if pos == self.end {
-                    'E'
-                } else if path.contains(&pos) {
-                    '▓'
-                } else {
-                    match tile {
-                        Tile::Wall => '▒',
-                        Tile::Open => ' ',
-                    }
-                };
-                print!("{}", c);
-            }
-            println!();
-        }
+    fn render(&self, name: &str, path: impl IntoIterator<Item = Vec2>) {
+        let renderer = Renderer::new(&self.maze, |tile| match tile {
+            Tile::Wall => Glyph::new('▒'),
+            Tile::Open => Glyph::new(' '),
+        })
+        .path(path, Glyph::colored('▓', Rgb::GREEN))
+        .cursor(self.start, Glyph::colored('S', Rgb::YELLOW))
+        .cursor(self.end, Glyph::colored('E', Rgb::YELLOW));
+
+        render::show("day_16::render", name, &renderer);
     }
 
-    fn find_shortest_path_cost(&self) -> i64 {
+    fn no_path() -> Error {
+        Error::NoSolution("No path leads from S to E".to_string())
+    }
+
+    fn find_shortest_path_cost(&self) -> Result<i64> {
         let mut solver = MazeSolver::new(&self.maze);
-        let cost = solver.shortest_path(self.start, self.end);
+        let cost = solver
+            .shortest_path(self.start, self.end)
+            .ok_or_else(Self::no_path)?;
 
-        if self.verbose {
+        if render::enabled("day_16::render") {
             let path = solver.reconstruct_path(self.start, self.end);
-            self.print(&path);
+            self.render("day_16-shortest-path", path);
         }
 
-        cost
+        Ok(cost)
     }
 
-    fn find_all_shortest_paths_nodes(self) -> i64 {
+    fn find_all_shortest_paths_nodes(&self) -> Result<i64> {
         let mut solver = MazeSolver::new(&self.maze);
-        let _ = solver.shortest_path(self.start, self.end);
+        solver
+            .shortest_path(self.start, self.end)
+            .ok_or_else(Self::no_path)?;
 
         let nodes = solver.all_shortest_paths_nodes(self.end);
-        if self.verbose {
-            self.print(&nodes);
+        if render::enabled("day_16::render") {
+            self.render("day_16-all-shortest-paths", nodes.iter().copied());
         }
 
-        nodes.len() as i64
+        Ok(nodes.len() as i64)
     }
 }
 
-pub fn run(input: Input, part: day::Part) -> Result<i64> {
-    let maze = ReindeerMaze::new(input, true);
+pub struct Day;
+
+impl Solution for Day {
+    type Model = ReindeerMaze;
 
-    let result = match part {
-        day::Part::One => maze.find_shortest_path_cost(),
-        day::Part::Two => maze.find_all_shortest_paths_nodes(),
-    } as i64;
+    const DAY: u8 = 16;
+    const TITLE: &'static str = "Reindeer Maze";
 
-    Ok(result)
+    fn parse(input: Input, _: &Params) -> Result<Self::Model> {
+        ReindeerMaze::new(input)
+    }
+
+    fn part_one(maze: &Self::Model) -> Result<Answer> {
+        Ok(maze.find_shortest_path_cost()?.into())
+    }
+
+    fn part_two(maze: &Self::Model) -> Result<Answer> {
+        Ok(maze.find_all_shortest_paths_nodes()?.into())
+    }
+}
+
+#[cfg(test)]
+const EXAMPLE: &str = "\
+###############
+#.......#....E#
+#.#.###.#.###.#
+#.....#.#...#.#
+#.###.#####.#.#
+#.#.#.......#.#
+#.#.#####.###.#
+#...........#.#
+###.#.#####.#.#
+#...#.....#.#.#
+#.#.#.###.#.#.#
+#.....#...#.#.#
+#.###.#.#.#.#.#
+#S..#.....#...#
+###############
+";
+
+example_tests! {
+    part_one: part_one(EXAMPLE) => 7036,
+    part_two: part_two(EXAMPLE) => 45,
+}
+
+#[cfg(test)]
+mod test_errors {
+    use super::*;
+
+    #[test]
+    fn end_out_of_reach() {
+        for input in ["#####\n#S#E#\n#####\n", "S.#E\n"] {
+            let maze = Day::parse(Input::from_str(input), &Params::new()).unwrap();
+
+            assert!(matches!(Day::part_one(&maze), Err(Error::NoSolution(_))));
+            assert!(matches!(Day::part_two(&maze), Err(Error::NoSolution(_))));
+        }
+    }
 }
 
 day_tests!("day_16-1.dat", 107468, 533);
@@ -1,9 +1,16 @@
+use crate::answer::Answer;
 use crate::error::Result;
 use crate::input::Input;
-use crate::{day, day_tests};
+use crate::params::{ParamSpec, Params};
+use crate::solution::Solution;
+use crate::util::budget::{self, Budget, Meter};
+use crate::util::parse;
+use crate::{day_tests, example_tests};
 
 use std::fmt::{self, Display, Formatter};
 
+use log::{info, trace};
+
 struct ComputerConfigReader {
     input: Input,
 }
@@ -15,35 +22,42 @@ impl ComputerConfigReader {
 }
 
 impl ComputerConfigReader {
-    fn read_register(&mut self) -> Option<i64> {
-        self.input
-            .read_line()
-            .expect("button input")
-            .split(':') // Register X: <value>
-            .skip(1)
-            .take(1)
-            .map(|s| s.trim().parse::<i64>().expect("a number"))
-            .next()
+    fn read_register(&mut self, name: char) -> Result<i64> {
+        let key = format!("Register {name}");
+        let line = parse::expect_line(&mut self.input, &format!("{key}: <value>"))?;
+
+        Ok(line.signed(line.value(&key)?)?)
     }
 
     fn skip_line(&mut self) {
         let _ = self.input.read_line();
     }
 
-    fn read_program(&mut self) -> Option<Vec<u8>> {
-        self.input
-            .read_line()
-            .expect("program input")
-            .split(':') // Program: <code>...
-            .skip(1)
-            .take(1)
-            .map(|s| {
-                s.trim()
-                    .split(',')
-                    .map(|s| s.parse::<u8>().expect("a number"))
-                    .collect::<Vec<_>>()
+    fn read_program(&mut self) -> Result<Vec<u8>> {
+        let line = parse::expect_line(&mut self.input, "Program: <value>")?;
+
+        let parts = line.value("Program")?.split(',').collect::<Vec<_>>();
+
+        let code = parts
+            .iter()
+            .map(|&s| match line.unsigned::<u8>(s) {
+                Ok(value) if value < 8 => Ok(value),
+                _ => Err(line.error(s, "a 3-bit number")),
             })
-            .next()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if !code.len().is_multiple_of(2) {
+            Err(line.line_error("pairs of opcodes and operands"))?
+        }
+
+        // the reserved combo operand 7 is rejected here, so that the program always disassembles
+        for (i, instr) in code.chunks_exact(2).enumerate() {
+            if Instr::takes_combo(instr[0]) && instr[1] == 7 {
+                Err(line.error(parts[2 * i + 1], "a combo operand 0-6"))?
+            }
+        }
+
+        Ok(code)
     }
 }
 
@@ -98,14 +112,13 @@ impl Arg {
         Arg::Literal(arg)
     }
 
+    // the reserved operand 7 never gets here, programs using it are rejected when parsing
     fn combo(arg: u8) -> Self {
         match arg {
             0..=3 => Arg::Literal(arg),
             4 => Arg::Register(Register::A),
             5 => Arg::Register(Register::B),
-            6 => Arg::Register(Register::C),
-            7 => panic!("Reserved operand value: b111"),
-            _ => panic!("Invalid operand: {:?}", arg),
+            _ => Arg::Register(Register::C),
         }
     }
 
@@ -130,6 +143,7 @@ enum Instr {
 }
 
 impl Instr {
+    // opcodes are 3-bit numbers, checked when parsing
     fn new(opcode: u8, arg: u8) -> Self {
         match opcode {
             0 => Instr::Adv(Arg::combo(arg)),
@@ -139,11 +153,13 @@ impl Instr {
             4 => Instr::Bxc(Arg::literal(arg)),
             5 => Instr::Out(Arg::combo(arg)),
             6 => Instr::Bdv(Arg::combo(arg)),
-            7 => Instr::Cdv(Arg::combo(arg)),
-
-            _ => panic!("Unknown opcode: {:?}", opcode),
+            _ => Instr::Cdv(Arg::combo(arg)),
         }
     }
+
+    fn takes_combo(opcode: u8) -> bool {
+        matches!(opcode, 0 | 2 | 5 | 6 | 7)
+    }
 }
 
 impl Display for Instr {
@@ -165,80 +181,69 @@ type MachineCode = Vec<u8>;
 type Asm = Vec<Instr>;
 type Registers = [i64; 3];
 
-struct Computer {
+#[derive(Clone)]
+pub struct Computer {
     code: MachineCode,
     asm: Asm,
 
     registers: Registers,
     ip: usize,
 
-    debug: bool,
-
     output: Vec<u8>,
 
     jmp_flag: bool,
+
+    budget: Budget,
 }
 
 impl Computer {
-    fn from_input(input: Input) -> Self {
+    fn from_input(input: Input, budget: Budget) -> Result<Self> {
         let mut reader = ComputerConfigReader::new(input);
 
-        let reg_a = reader.read_register().expect("register A");
-        let reg_b = reader.read_register().expect("register B");
-        let reg_c = reader.read_register().expect("register C");
+        let reg_a = reader.read_register('A')?;
+        let reg_b = reader.read_register('B')?;
+        let reg_c = reader.read_register('C')?;
 
         reader.skip_line();
 
-        let code = reader.read_program().expect("program");
+        let code = reader.read_program()?;
         let asm = Self::disassemble(&code);
 
-        Self {
+        Ok(Self {
             code,
             asm,
             registers: [reg_a, reg_b, reg_c],
             ip: 0,
-            debug: false,
             output: vec![],
             jmp_flag: false,
-        }
+            budget,
+        })
     }
 
     #[allow(dead_code)]
-    fn new(asm: Asm, initial_registers: Registers, debug: bool) -> Self {
+    fn new(asm: Asm, initial_registers: Registers) -> Self {
         Self {
             code: vec![],
             asm,
             registers: initial_registers,
             ip: 0,
-            debug,
             output: vec![],
             jmp_flag: false,
-        }
-    }
-
-    fn dbg(&self, msg: &str) {
-        if self.debug {
-            println!("{}", msg);
+            budget: Budget::default(),
         }
     }
 
     fn disassemble(code: &MachineCode) -> Asm {
-        code.chunks(2)
-            .map(|instr| {
-                if let [opcode, arg] = instr {
-                    Instr::new(*opcode, *arg)
-                } else {
-                    panic!("Invalid instruction: {:?}", instr);
-                }
-            })
+        code.chunks_exact(2)
+            .map(|instr| Instr::new(instr[0], instr[1]))
             .collect::<Vec<_>>()
     }
 
     fn print_state(&self) {
-        println!("Registers: {:?}", self.registers);
-        println!("Code: {:?}", self.code);
-        println!("IP: {:?}", self.ip);
-        println!("Output: {:?}", self.output);
+        trace!(target: "day_17::trace", "Registers: {:?}", self.registers);
+        trace!(target: "day_17::trace", "Code: {:?}", self.code);
+        trace!(target: "day_17::trace", "IP: {:?}", self.ip);
+        trace!(target: "day_17::trace", "Output: {:?}", self.output);
     }
 
     fn exec_instr(&mut self, instr: Instr) {
@@ -249,7 +254,7 @@ impl Computer {
 
                 let res = left / right;
 
-                self.dbg(&format!("adv ({arg}| {} / {} = {}", left, right, res));
+                trace!(target: "day_17::trace", "adv ({arg}| {} / {} = {}", left, right, res);
 
                 self[Register::A] = res;
             }
@@ -260,7 +265,7 @@ impl Computer {
 
                 let res = left ^ right;
 
-                self.dbg(&format!("bxl ({arg})| {left} ^ {right} = {res}"));
+                trace!(target: "day_17::trace", "bxl ({arg})| {left} ^ {right} = {res}");
 
                 self[Register::B] = res;
             }
@@ -269,7 +274,7 @@ impl Computer {
                 let x = arg.value(self);
                 let res = arg.value(self) % 8;
 
-                self.dbg(&format!("bst ({arg})| {} % 8 = {}", x, res));
+                trace!(target: "day_17::trace", "bst ({arg})| {} % 8 = {}", x, res);
 
                 self[Register::B] = res;
             }
@@ -280,12 +285,12 @@ impl Computer {
                 if a != 0 {
                     let ip = arg.value(self) as usize;
 
-                    self.dbg(&format!("jnz ({arg})| *A = {}, jump to {}", a, ip));
+                    trace!(target: "day_17::trace", "jnz ({arg})| *A = {}, jump to {}", a, ip);
 
                     self.ip = ip / 2;
                     self.jmp_flag = true;
                 } else {
-                    self.dbg(&format!("jnz ({arg})| *A = {}, nop", a));
+                    trace!(target: "day_17::trace", "jnz ({arg})| *A = {}, nop", a);
                 }
             }
 
@@ -295,7 +300,7 @@ impl Computer {
 
                 let res = b ^ c;
 
-                self.dbg(&format!("bxc (_)| {} ^ {} = {}", b, c, res));
+                trace!(target: "day_17::trace", "bxc (_)| {} ^ {} = {}", b, c, res);
 
                 self[Register::B] = res;
             }
@@ -304,10 +309,10 @@ impl Computer {
                 let val = arg.value(self);
                 let res = val % 8;
 
-                self.dbg(&format!(
+                trace!(target: "day_17::trace",
                     "*out ({arg})| {} -> {} | {:?}",
                     val, res, self.output
-                ));
+                );
 
                 self.output.push(res as u8);
             }
@@ -318,7 +323,7 @@ impl Computer {
 
                 let res = left / right;
 
-                self.dbg(&format!("bdv ({arg})| {} / {} = {}", left, right, res));
+                trace!(target: "day_17::trace", "bdv ({arg})| {} / {} = {}", left, right, res);
 
                 self[Register::B] = res;
             }
@@ -329,15 +334,16 @@ impl Computer {
 
                 let res = left / right;
 
-                self.dbg(&format!("cdv ({}) | {} / {} = {}", arg, left, right, res));
+                trace!(target: "day_17::trace", "cdv ({}) | {} / {} = {}", arg, left, right, res);
 
                 self[Register::C] = res;
             }
         }
     }
 
-    fn exec(&mut self) {
+    fn exec(&mut self, meter: &mut Meter) -> Result<()> {
         while let Some(instr) = self.asm.get(self.ip) {
+            meter.step()?;
             self.exec_instr(*instr);
 
             if self.jmp_flag {
@@ -346,20 +352,20 @@ impl Computer {
                 self.ip += 1;
             }
         }
+
+        Ok(())
     }
 
-    fn run_program(&mut self) -> i64 {
-        self.exec();
+    fn run_program(&mut self) -> Result<String> {
+        let mut meter = self.budget.start("run_program");
+        self.exec(&mut meter)?;
 
-        let output = self
+        Ok(self
             .output
             .iter()
             .map(|x| x.to_string())
-            .collect::<Vec<_>>();
-
-        println!("{}", output.join(","));
-
-        output.join("").parse::<i64>().expect("a number")
+            .collect::<Vec<_>>()
+            .join(","))
     }
 
     fn reset(&mut self, a: i64) {
@@ -368,48 +374,30 @@ impl Computer {
         self.output.clear();
     }
 
-    fn test_a(&mut self, a: i64, expected_vec: &[u8]) -> bool {
-        println!("testing A = {} ~ {:?} ", a, expected_vec);
-
-        let mut a = a;
-
-        for i in (0..expected_vec.len()).rev() {
-            let expected = expected_vec[i];
-            print!("\ttrying A = {} ~ {:?} ", a, expected);
+    fn test_a(&mut self, a: i64, expected: &[u8], meter: &mut Meter) -> Result<bool> {
+        trace!(target: "day_17::trace", "testing A = {} ~ {:?} ", a, expected);
 
-            let amod8 = a % 8;
-            let amod8xor5 = amod8 ^ 5;
-            let res = (a / 2_i64.pow(amod8xor5 as u32) ^ amod8xor5 ^ 6) % 8;
+        self.reset(a);
+        self.exec(meter)?;
 
-            if res as u8 != expected {
-                println!("=> false");
-                return false;
-            } else {
-                println!("=> true");
-            }
-            a = a / 8;
-        }
-
-        true
+        Ok(self.output == expected)
     }
 
-    fn find_a(&mut self) -> i64 {
-        self.exec();
-
-        let program = [2, 4, 1, 5, 7, 5, 4, 3, 1, 6, 0, 3, 5, 5, 3, 0];
-
-        self.reset(0);
+    fn find_a(&mut self) -> Result<i64> {
+        let program = self.code.clone();
 
         let mut aa = 0;
         let mut result_a = 0;
 
-        let mut expected: Vec<u8> = vec![];
+        let mut meter = self.budget.start("find_a");
 
-        for out in program.iter().rev() {
-            expected.push(*out);
+        for len in 1..=program.len() {
+            let expected = &program[program.len() - len..];
             let mut a = aa;
             loop {
-                if self.test_a(a, expected.as_slice()) {
+                meter.step()?;
+
+                if self.test_a(a, expected, &mut meter)? {
                     aa = a * 8;
                     result_a = a;
                     break;
@@ -418,23 +406,40 @@ impl Computer {
             }
         }
 
-        println!("A to produce: {:?}: {}", expected, result_a);
+        info!(target: "day_17::progress", "A to produce: {:?}: {}", program, result_a);
 
-        result_a
+        Ok(result_a)
     }
 }
 
-pub fn run(input: Input, part: day::Part) -> Result<i64> {
-    let mut computer = Computer::from_input(input);
+pub struct Day;
+
+impl Solution for Day {
+    type Model = Computer;
+
+    const DAY: u8 = 17;
+    const TITLE: &'static str = "Chronospatial Computer";
+    const PARAMS: &'static [&'static dyn ParamSpec] = &[&budget::TIMEOUT, &budget::MAX_STEPS];
+
+    fn parse(input: Input, params: &Params) -> Result<Self::Model> {
+        let computer = Computer::from_input(input, Budget::from_params(params)?)?;
+
+        computer.print_state();
 
-    computer.print_state();
+        Ok(computer)
+    }
+
+    fn part_one(computer: &Self::Model) -> Result<Answer> {
+        let mut computer = computer.clone();
+
+        Ok(computer.run_program()?.into())
+    }
 
-    let result = match part {
-        day::Part::One => computer.run_program(),
-        day::Part::Two => computer.find_a(),
-    };
+    fn part_two(computer: &Self::Model) -> Result<Answer> {
+        let mut computer = computer.clone();
 
-    Ok(result)
+        Ok(computer.find_a()?.into())
+    }
 }
 
 #[cfg(test)]
@@ -445,8 +450,8 @@ mod test_instructions {
     fn adv_instr() {
         let asm = vec![Instr::Adv(Arg::Literal(2))];
 
-        let mut computer = Computer::new(asm, [11, 0, 0], true);
-        computer.exec();
+        let mut computer = Computer::new(asm, [11, 0, 0]);
+        computer.exec(&mut Budget::default().start("test")).unwrap();
 
         assert_eq!(computer[Register::A], 2);
     }
@@ -455,8 +460,8 @@ mod test_instructions {
     fn bxl_instr() {
         let asm = vec![Instr::Bxl(Arg::Literal(0b010))];
 
-        let mut computer = Computer::new(asm, [0, 15, 0], true);
-        computer.exec();
+        let mut computer = Computer::new(asm, [0, 15, 0]);
+        computer.exec(&mut Budget::default().start("test")).unwrap();
 
         assert_eq!(computer[Register::B], 13);
     }
@@ -465,8 +470,8 @@ mod test_instructions {
     fn bst_instr_1() {
         let asm = vec![Instr::Bst(Arg::combo(2))];
 
-        let mut computer = Computer::new(asm, [0, 0, 0], true);
-        computer.exec();
+        let mut computer = Computer::new(asm, [0, 0, 0]);
+        computer.exec(&mut Budget::default().start("test")).unwrap();
 
         assert_eq!(computer[Register::B], 2);
     }
@@ -475,8 +480,8 @@ mod test_instructions {
     fn bst_instr_2() {
         let asm = vec![Instr::Bst(Arg::combo(4))];
 
-        let mut computer = Computer::new(asm, [39, 0, 0], true);
-        computer.exec();
+        let mut computer = Computer::new(asm, [39, 0, 0]);
+        computer.exec(&mut Budget::default().start("test")).unwrap();
 
         assert_eq!(computer[Register::B], 7);
     }
@@ -485,8 +490,8 @@ mod test_instructions {
     fn bxc_instr() {
         let asm = vec![Instr::Bxc(Arg::literal(0))];
 
-        let mut computer = Computer::new(asm, [0, 15, 1], true);
-        computer.exec();
+        let mut computer = Computer::new(asm, [0, 15, 1]);
+        computer.exec(&mut Budget::default().start("test")).unwrap();
 
         assert_eq!(computer[Register::B], 14);
     }
@@ -500,8 +505,8 @@ mod test_instructions {
             Instr::Out(Arg::combo(6)), // prints (*C % 8)
         ];
 
-        let mut computer = Computer::new(asm, [100, 101, 102], true);
-        computer.exec();
+        let mut computer = Computer::new(asm, [100, 101, 102]);
+        computer.exec(&mut Budget::default().start("test")).unwrap();
 
         assert_eq!(computer.output, vec![3, 4, 5, 6]);
     }
@@ -510,8 +515,8 @@ mod test_instructions {
     fn bdv_instr() {
         let asm = vec![Instr::Bdv(Arg::combo(6))];
 
-        let mut computer = Computer::new(asm, [33, 0, 3], true);
-        computer.exec();
+        let mut computer = Computer::new(asm, [33, 0, 3]);
+        computer.exec(&mut Budget::default().start("test")).unwrap();
 
         assert_eq!(computer[Register::B], 4);
     }
@@ -520,8 +525,8 @@ mod test_instructions {
     fn cdv_instr() {
         let asm = vec![Instr::Cdv(Arg::combo(5))];
 
-        let mut computer = Computer::new(asm, [33, 2, 0], true);
-        computer.exec();
+        let mut computer = Computer::new(asm, [33, 2, 0]);
+        computer.exec(&mut Budget::default().start("test")).unwrap();
 
         assert_eq!(computer[Register::C], 8);
     }
@@ -530,15 +535,77 @@ mod test_instructions {
     fn cdv_instr_2() {
         let asm = vec![Instr::Cdv(Arg::combo(3))];
 
-        let mut computer = Computer::new(asm, [33, 0, 0], true);
-        computer.exec();
+        let mut computer = Computer::new(asm, [33, 0, 0]);
+        computer.exec(&mut Budget::default().start("test")).unwrap();
 
         assert_eq!(computer[Register::C], 4);
     }
+
+    #[test]
+    fn reserved_combo_operand() {
+        let input = "Register A: 0\nRegister B: 0\nRegister C: 0\n\nProgram: 1,7,0,7\n";
+
+        let error = match Computer::from_input(Input::from_str(input), Budget::default()) {
+            Err(crate::error::Error::Parse(error)) => error,
+            _ => panic!("expected a parse error"),
+        };
+
+        assert_eq!((error.line, error.column), (5, 16));
+        assert_eq!(error.expected, "a combo operand 0-6");
+    }
+}
+
+#[cfg(test)]
+mod test_budget {
+    use super::*;
+    use crate::error::Error;
+
+    const ENDLESS: &str = "Register A: 1\nRegister B: 0\nRegister C: 0\n\nProgram: 3,0\n";
+
+    fn parse(input: &str, params: Params) -> Result<Computer> {
+        Day::parse(Input::from_str(input), &params)
+    }
+
+    #[test]
+    fn finds_a_for_the_program_read() {
+        let input = "\
+Register A: 0
+Register B: 0
+Register C: 0
+
+Program: 2,4,1,5,7,5,4,3,1,6,0,3,5,5,3,0
+";
+        let computer = parse(input, Params::new()).unwrap();
+
+        assert_eq!(Day::part_two(&computer).unwrap(), 105734774294938);
+    }
+
+    #[test]
+    fn endless_program_runs_out_of_budget() {
+        let computer = parse(ENDLESS, Params::new().with("max_steps", 10)).unwrap();
+
+        assert!(matches!(
+            Day::part_one(&computer),
+            Err(Error::OutOfBudget("run_program", _))
+        ));
+        assert!(matches!(
+            Day::part_two(&computer),
+            Err(Error::OutOfBudget("find_a", _))
+        ));
+    }
+}
+
+#[cfg(test)]
+const EXAMPLE: &str = "\
+Register A: 729
+Register B: 0
+Register C: 0
+
+Program: 0,1,5,4,3,0
+";
+
+example_tests! {
+    part_one: part_one(EXAMPLE) => "4,6,3,5,6,3,5,2,1,0",
 }
 
-day_tests!(
-    "day_17-1.dat",
-    735757430, /* 7,3,5,7,5,7,4,3,0 */
-    105734774294938
-);
+day_tests!("day_17-1.dat", "7,3,5,7,5,7,4,3,0", 105734774294938);
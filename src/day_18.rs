@@ -1,10 +1,20 @@
-use crate::error::{Error, Result};
+use crate::answer::Answer;
+use crate::error::{Error, ParseError, Result};
 use crate::input::Input;
+use crate::params::{Param, ParamSpec, Params};
+use crate::solution::Solution;
+use crate::util::budget::{self, Budget};
+use crate::util::grid::Grid;
+use crate::util::parse;
+use crate::util::render::{self, Glyph, Renderer, Rgb};
 use crate::util::Vec2;
-use crate::{day, day_tests};
+use crate::{day_tests, example_tests};
 
 use std::collections::{HashSet, VecDeque};
-use std::str::FromStr;
+
+const WIDTH: Param<usize> = Param::new("width", 71).at_least(1);
+const HEIGHT: Param<usize> = Param::new("height", 71).at_least(1);
+const BYTES: Param<usize> = Param::new("bytes", 1024);
 
 #[derive(Debug, Clone, PartialEq)]
 enum MemCell {
@@ -12,45 +22,61 @@ enum MemCell {
     Corrupted,
 }
 
-type Memory = Vec<Vec<MemCell>>;
+type Memory = Grid<MemCell>;
 
-struct CorruptedMemory {
+#[derive(Clone)]
+pub struct CorruptedMemory {
     ram: Memory,
     bounds: Vec2,
     falling_bytes: Vec<Vec2>,
     falling_bytes_index: usize,
     first_wave_size: usize,
+    budget: Budget,
 }
 
 impl CorruptedMemory {
-    fn new(input: Input, width: usize, height: usize, first_wave_size: usize) -> Self {
-        let falling_bytes = input
-            .lines()
-            .map(|line| Vec2::from_str(line.unwrap().as_str()).unwrap())
-            .collect();
-
-        Self {
-            ram: vec![vec![MemCell::Safe; width]; height],
-            bounds: (width, height).into(),
+    fn new(
+        mut input: Input,
+        width: usize,
+        height: usize,
+        first_wave_size: usize,
+        budget: Budget,
+    ) -> Result<Self> {
+        let bounds = Vec2::cell(width, height);
+
+        let falling_bytes = parse::lines(&mut input)
+            .map(|line| {
+                let expected = format!("a position X,Y inside the {width}x{height} memory");
+
+                match line.vec2(&line) {
+                    Ok(pos) if pos.inside(&bounds) => Ok(pos),
+                    _ => Err(line.line_error(expected)),
+                }
+            })
+            .collect::<Result<_, ParseError>>()?;
+
+        Ok(Self {
+            ram: Grid::with_size(width, height, MemCell::Safe),
+            bounds,
             falling_bytes,
             falling_bytes_index: 0,
             first_wave_size,
-        }
+            budget,
+        })
     }
 
-    fn print_ram(&self) {
-        for row in &self.ram {
-            for cell in row {
-                print!(
-                    "{}",
-                    match cell {
-                        MemCell::Safe => '.',
-                        MemCell::Corrupted => '#',
-                    }
-                );
-            }
-            println!();
+    fn render_ram(&self, name: &str, cut_off_byte: Option<Vec2>) {
+        if !render::enabled("day_18::render") {
+            return;
         }
+
+        let renderer = Renderer::new(&self.ram, |cell| match cell {
+            MemCell::Safe => Glyph::new('.'),
+            MemCell::Corrupted => Glyph::colored('#', Rgb::RED),
+        })
+        .highlight(cut_off_byte, Rgb::YELLOW);
+
+        render::show("day_18::render", name, &renderer);
     }
 
     fn apply_falling_bytes(&mut self, count: usize) {
@@ -70,14 +96,8 @@ impl CorruptedMemory {
                 return Some(cost);
             }
 
-            for adj in pos.neighbours() {
-                let new_pos = if adj.inside(&self.bounds) {
-                    adj
-                } else {
-                    continue;
-                };
-
-                if self.ram[new_pos] == MemCell::Corrupted {
+            for (new_pos, cell) in self.ram.neighbours4(pos) {
+                if *cell == MemCell::Corrupted {
                     continue;
                 }
 
@@ -94,50 +114,165 @@ impl CorruptedMemory {
 
     fn find_escape_path(&mut self) -> Result<i64> {
         self.apply_falling_bytes(self.first_wave_size);
-        self.print_ram();
+        self.render_ram("day_18-first-wave", None);
 
-        self.find_path(Vec2::new(0, 0), self.bounds - &Vec2::new(1, 1))
+        self.find_path(Vec2::new(0, 0), self.bounds - Vec2::new(1, 1))
             .ok_or(Error::NoSolution(format!(
                 "No path found after {} bytes fell",
                 self.first_wave_size
             )))
     }
 
-    fn find_cut_off_byte(&mut self) -> i64 {
+    fn find_cut_off_byte(&mut self) -> Result<Vec2> {
         self.apply_falling_bytes(self.first_wave_size); // unwind first 1k, we know the path is there
 
-        self.print_ram();
-
         let from = Vec2::new(0, 0);
-        let to = self.bounds - &Vec2::new(1, 1);
+        let to = self.bounds - Vec2::new(1, 1);
 
         // this is stupid solution but the input is so small and find_path so quick it doesn't matter
-        while let Some(_) = self.find_path(from, to) {
+        let mut meter = self.budget.start("find_cut_off_byte");
+        while self.find_path(from, to).is_some() {
+            if self.falling_bytes_index == self.falling_bytes.len() {
+                return Err(Error::NoSolution(format!(
+                    "The path is still open after all {} bytes fell",
+                    self.falling_bytes.len()
+                )));
+            }
+
+            meter.step()?;
             self.apply_falling_bytes(1);
         }
 
         let cut_off_byte = self.falling_bytes[self.falling_bytes_index - 1];
-        println!("{},{}", cut_off_byte.x, cut_off_byte.y);
+        self.render_ram("day_18-cut-off", Some(cut_off_byte));
+
+        Ok(cut_off_byte)
+    }
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    type Model = CorruptedMemory;
+
+    const DAY: u8 = 18;
+    const TITLE: &'static str = "RAM Run";
+    const PARAMS: &'static [&'static dyn ParamSpec] = &[
+        &WIDTH,
+        &HEIGHT,
+        &BYTES,
+        &budget::TIMEOUT,
+        &budget::MAX_STEPS,
+    ];
+
+    fn parse(input: Input, params: &Params) -> Result<Self::Model> {
+        let bytes = params.get(&BYTES)?;
+        let memory = CorruptedMemory::new(
+            input,
+            params.get(&WIDTH)?,
+            params.get(&HEIGHT)?,
+            bytes,
+            Budget::from_params(params)?,
+        )?;
 
-        // we curently don't have a way of returing string as a day task result
-        let Vec2 { x, y } = cut_off_byte;
-        100 * x + y
+        // the first wave has to fall before anything else happens
+        if bytes > memory.falling_bytes.len() {
+            return Err(Error::InvalidParam(BYTES.name, bytes.to_string()));
+        }
+
+        Ok(memory)
+    }
+
+    fn part_one(mem: &Self::Model) -> Result<Answer> {
+        let mut mem = mem.clone();
+
+        Ok(mem.find_escape_path()?.into())
+    }
+
+    fn part_two(mem: &Self::Model) -> Result<Answer> {
+        let mut mem = mem.clone();
+
+        Ok(mem.find_cut_off_byte()?.into())
     }
 }
 
-pub fn run(input: Input, part: day::Part) -> Result<i64> {
-    let mut mem = if let day::Part::Two = part {
-        CorruptedMemory::new(input, 71, 71, 1024)
-    } else {
-        CorruptedMemory::new(input, 71, 71, 1024)
-    };
+#[cfg(test)]
+const EXAMPLE: &str = "\
+5,4
+4,2
+4,5
+3,0
+2,1
+6,3
+2,4
+1,5
+0,6
+3,3
+2,6
+5,1
+1,2
+5,5
+2,5
+6,5
+1,4
+0,4
+6,4
+1,1
+6,1
+1,0
+0,5
+1,6
+2,0
+";
 
-    let result = match part {
-        day::Part::One => mem.find_escape_path()?,
-        day::Part::Two => mem.find_cut_off_byte(),
-    } as i64;
+example_tests! {
+    part_one: part_one(EXAMPLE, width = 7, height = 7, bytes = 12) => 22,
+    part_two: part_two(EXAMPLE, width = 7, height = 7, bytes = 12) => "6,1",
+}
 
-    Ok(result)
+#[cfg(test)]
+mod test_errors {
+    use super::*;
+
+    fn parse(input: &str, params: Params) -> Result<CorruptedMemory> {
+        Day::parse(Input::from_str(input), &params)
+    }
+
+    #[test]
+    fn path_never_cut_off() {
+        let params = Params::new()
+            .with("width", 7)
+            .with("height", 7)
+            .with("bytes", 1);
+        let memory = parse("1,1\n2,2\n", params).unwrap();
+
+        assert!(matches!(Day::part_two(&memory), Err(Error::NoSolution(_))));
+    }
+
+    #[test]
+    fn positions_are_comma_separated() {
+        let params = Params::new()
+            .with("width", 7)
+            .with("height", 7)
+            .with("bytes", 1);
+
+        for input in ["1 2\n", "1|2\n", "1,2,3\n", "7,0\n"] {
+            assert!(matches!(
+                parse(input, params.clone()),
+                Err(Error::Parse(e)) if e.line == 1
+            ));
+        }
+    }
+
+    #[test]
+    fn more_bytes_than_the_input_has() {
+        let params = Params::new().with("width", 7).with("height", 7);
+
+        assert!(matches!(
+            parse(EXAMPLE, params),
+            Err(Error::InvalidParam("bytes", _))
+        ));
+    }
 }
 
-day_tests!("day_18-1.dat", 246, 2250);
+day_tests!("day_18-1.dat", 246, "22,50");
@@ -1,8 +1,13 @@
 use std::collections::HashMap;
 
-use crate::error::Result;
+use crate::answer::Answer;
+use crate::error::{ParseError, Result};
 use crate::input::Input;
-use crate::{day, day_tests};
+use crate::params::Params;
+use crate::solution::Solution;
+use crate::util::parallel;
+use crate::util::parse::{self, Line};
+use crate::{day_tests, example_tests};
 
 type Pattern = Vec<u8>;
 type Design = Vec<u8>;
@@ -16,50 +21,51 @@ impl InputReader {
         Self { input }
     }
 
-    fn read_patterns(&mut self) -> Vec<Pattern> {
-        self.input
-            .read_line()
-            .unwrap()
+    fn read_section(&mut self, expected: &str) -> Result<Vec<Line>, ParseError> {
+        parse::read_section(&mut self.input)
+            .ok_or_else(|| ParseError::eof(self.input.line_number() + 1, expected))
+    }
+
+    fn read_patterns(&mut self) -> Result<Vec<Pattern>> {
+        let section = self.read_section("towel patterns like r, wr, b")?;
+        let [line] = section.as_slice() else {
+            return Err(section[1]
+                .line_error("a blank line before the designs")
+                .into());
+        };
+
+        Ok(line
             .trim()
             .split(", ")
             .map(|s| s.as_bytes().to_vec())
-            .collect()
+            .collect())
     }
 
-    fn skip_line(&mut self) {
-        self.input.read_line().unwrap();
-    }
+    fn read_designs(&mut self) -> Result<Vec<Design>> {
+        let section = self.read_section("towel designs")?;
 
-    fn read_designs(self) -> Vec<Design> {
-        self.input
-            .lines()
-            .map(|line| line.unwrap().as_bytes().to_vec())
-            .collect()
+        Ok(section
+            .iter()
+            .map(|line| line.trim().as_bytes().to_vec())
+            .collect())
     }
 }
 
 type Cache<'a> = HashMap<&'a [u8], i64>;
 
-struct OnsenTowels {
+pub struct OnsenTowels {
     patterns: Vec<Pattern>,
     designs: Vec<Design>,
 }
 
 impl OnsenTowels {
-    fn new(input: Input) -> Self {
+    fn new(input: Input) -> Result<Self> {
         let mut reader = InputReader::new(input);
 
-        let patterns = reader.read_patterns();
-        reader.skip_line();
-        let designs = reader.read_designs();
-
-        Self { patterns, designs }
-    }
+        let patterns = reader.read_patterns()?;
+        let designs = reader.read_designs()?;
 
-    fn _print(slice: &[u8]) {
-        for c in slice {
-            print!("{}", *c as char);
-        }
+        Ok(Self { patterns, designs })
     }
 
     fn test_all<'a>(&self, design: &'a [u8], cache: &mut Cache<'a>) -> i64 {
@@ -95,13 +101,10 @@ impl OnsenTowels {
     }
 
     fn match_designs(&self) -> Vec<usize> {
-        self.designs
-            .iter()
-            .map(|design| {
-                let mut cache = Cache::new();
-                self.test_all(design, &mut cache) as usize
-            })
-            .collect::<Vec<_>>()
+        parallel::map(&self.designs, |design| {
+            let mut cache = Cache::new();
+            self.test_all(design, &mut cache) as usize
+        })
     }
 
     fn count_feasible_designs(&self) -> usize {
@@ -113,15 +116,44 @@ impl OnsenTowels {
     }
 }
 
-pub fn run(input: Input, part: day::Part) -> Result<i64> {
-    let towels = OnsenTowels::new(input);
+pub struct Day;
+
+impl Solution for Day {
+    type Model = OnsenTowels;
+
+    const DAY: u8 = 19;
+    const TITLE: &'static str = "Linen Layout";
+
+    fn parse(input: Input, _: &Params) -> Result<Self::Model> {
+        OnsenTowels::new(input)
+    }
 
-    let result = match part {
-        day::Part::One => towels.count_feasible_designs(),
-        day::Part::Two => towels.count_all_arrangements(),
-    } as i64;
+    fn part_one(towels: &Self::Model) -> Result<Answer> {
+        Ok(towels.count_feasible_designs().into())
+    }
+
+    fn part_two(towels: &Self::Model) -> Result<Answer> {
+        Ok(towels.count_all_arrangements().into())
+    }
+}
 
-    Ok(result)
+#[cfg(test)]
+const EXAMPLE: &str = "\
+r, wr, b, g, bwu, rb, gb, br
+
+brwrr
+bggr
+gbbr
+rrbgbr
+ubwu
+bwurrg
+brgr
+bbrgwb
+";
+
+example_tests! {
+    part_one: part_one(EXAMPLE) => 6,
+    part_two: part_two(EXAMPLE) => 16,
 }
 
 day_tests!("day_19-1.dat", 358, 600639829400603);
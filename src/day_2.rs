@@ -1,8 +1,9 @@
-use crate::error::Result;
+use crate::answer::Answer;
+use crate::error::{ParseError, Result};
 use crate::input::Input;
-use crate::{day, day_tests};
-
-use log::info;
+use crate::params::Params;
+use crate::solution::Solution;
+use crate::{day_tests, example_tests};
 
 use std::iter::Peekable;
 
@@ -17,14 +18,21 @@ impl InputReader {
 }
 
 impl Iterator for InputReader {
-    type Item = Vec<i64>;
+    type Item = Result<Vec<i64>>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.input
-            .read_line()?
+        let line = self.input.read_line()?;
+        let line_no = self.input.line_number();
+
+        let record = line
             .split_whitespace()
-            .map(|s| s.parse().ok())
-            .collect::<Option<Vec<i64>>>()
+            .map(|s| {
+                s.parse()
+                    .map_err(|_| ParseError::at(line_no, &line, s, "a level").into())
+            })
+            .collect();
+
+        Some(record)
     }
 }
 
@@ -69,7 +77,7 @@ impl TryFrom<(i64, i64)> for Monotonicity {
         let diff = b - a;
         let dist = diff.abs();
 
-        if dist < 1 || dist > 3 {
+        if !(1..=3).contains(&dist) {
             return Err(());
         }
 
@@ -85,14 +93,14 @@ struct RecordCheckError {
     index: usize,
 }
 
-struct UnusualData {
+pub struct UnusualData {
     records: Vec<Record>,
 }
 
 impl UnusualData {
-    fn new(input: Input) -> Self {
-        let records = InputReader::new(input).collect();
-        Self { records }
+    fn new(input: Input) -> Result<Self> {
+        let records = InputReader::new(input).collect::<Result<_>>()?;
+        Ok(Self { records })
     }
 
     fn record_it_with_item_skip(
@@ -130,7 +138,7 @@ impl UnusualData {
     }
 
     fn check_record(
-        record: &Vec<i64>,
+        record: &[i64],
         skip_item: Option<usize>,
     ) -> Result<Monotonicity, RecordCheckError> {
         match skip_item {
@@ -139,11 +147,11 @@ impl UnusualData {
         }
     }
 
-    fn check_record_simple(record: &Vec<i64>) -> bool {
+    fn check_record_simple(record: &[i64]) -> bool {
         Self::check_record(record, None).is_ok()
     }
 
-    fn check_record_fault_tolerant(record: &Vec<i64>) -> bool {
+    fn check_record_fault_tolerant(record: &[i64]) -> bool {
         let failure_index = match Self::check_record(record, None) {
             Ok(_) => return true,
             Err(RecordCheckError { index }) => {
@@ -180,18 +188,40 @@ impl UnusualData {
     }
 }
 
-#[allow(unreachable_code, unused_variables, unused_mut)]
-pub fn run(mut input: Input, part: day::Part) -> Result<i64> {
-    let data = UnusualData::new(input);
+pub struct Day;
+
+impl Solution for Day {
+    type Model = UnusualData;
 
-    let result = match part {
-        day::Part::One => data.count_valid_records(),
-        day::Part::Two => data.count_valid_records_with_fault_tolerance(),
-    } as i64;
+    const DAY: u8 = 2;
+    const TITLE: &'static str = "Red-Nosed Reports";
 
-    info!("Day done ✅");
+    fn parse(input: Input, _: &Params) -> Result<Self::Model> {
+        UnusualData::new(input)
+    }
+
+    fn part_one(data: &Self::Model) -> Result<Answer> {
+        Ok(data.count_valid_records().into())
+    }
+
+    fn part_two(data: &Self::Model) -> Result<Answer> {
+        Ok(data.count_valid_records_with_fault_tolerance().into())
+    }
+}
 
-    Ok(result)
+#[cfg(test)]
+const EXAMPLE: &str = "\
+7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9
+";
+
+example_tests! {
+    part_one: part_one(EXAMPLE) => 2,
+    part_two: part_two(EXAMPLE) => 4,
 }
 
 day_tests!("day_2-1.dat", 534, 577);
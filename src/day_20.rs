@@ -1,10 +1,18 @@
 use std::collections::HashSet;
 
-use crate::error::Result;
+use crate::answer::Answer;
+use crate::error::{Error, Result};
 use crate::input::Input;
+use crate::params::{Param, ParamSpec, Params};
+use crate::solution::Solution;
 use crate::util::grid::Grid;
+use crate::util::render::{self, Glyph, Renderer, Rgb};
 use crate::util::Vec2;
-use crate::{day, day_tests};
+use crate::{day_tests, example_tests};
+
+const THRESHOLD: Param<i64> = Param::new("threshold", 100);
+const RADIUS_ONE: Param<i64> = Param::new("radius_one", 2).at_least(0);
+const RADIUS_TWO: Param<i64> = Param::new("radius_two", 20).at_least(0);
 
 #[derive(Debug, Clone, Copy)]
 enum Tile {
@@ -23,67 +31,60 @@ impl MazeInputReader {
         Self { input }
     }
 
-    fn read(&mut self) -> (Vec<Vec<Tile>>, Vec2, Vec2) {
-        let mut y = 0;
-        let mut start = Vec2::new(0, 0);
-        let mut end = Vec2::new(0, 0);
-
-        let mut track = vec![];
+    fn read(&mut self) -> Result<(Track, Vec2, Vec2)> {
+        let (track, markers) = Grid::parse(&mut self.input, &['S', 'E'], |c| match c {
+            '#' => Ok(Tile::Wall),
+            '.' | 'S' | 'E' => Ok(Tile::Path(0)),
+            _ => Err("a track tile #, ., S or E"),
+        })?;
 
-        while let Some(line) = self.input.read_line() {
-            let line = line.trim();
-            if line.is_empty() {
-                break;
-            }
-
-            let row = line
-                .char_indices()
-                .map(|(x, c)| match c {
-                    '#' => Tile::Wall,
-                    '.' => Tile::Path(0),
-                    'S' => {
-                        start = (x, y).into();
-                        Tile::Path(0)
-                    }
-                    'E' => {
-                        end = (x, y).into();
-                        Tile::Path(0)
-                    }
-                    _ => panic!("Unknown maze tile: {}", c),
-                })
-                .collect::<Vec<_>>();
-
-            track.push(row);
-
-            y += 1;
-        }
-
-        (track, start, end)
+        Ok((track, markers.single('S')?, markers.single('E')?))
     }
 }
 
-struct RaceTrack {
+pub struct RaceTrack {
     track: Track,
     start: Vec2,
     end: Vec2,
+    threshold: i64,           // minimal time a cheat has to save
+    cheat_radius: (i64, i64), // cheat lengths of part one and two
 }
 
 impl RaceTrack {
-    fn from_input(input: Input) -> Self {
+    fn from_input(input: Input, threshold: i64, cheat_radius: (i64, i64)) -> Result<Self> {
         let mut reader = MazeInputReader::new(input);
-        let (track, start, end) = reader.read();
+        let (track, start, end) = reader.read()?;
 
         let mut inst = Self {
-            track: Grid::new(track),
+            track,
             start,
             end,
+            threshold,
+            cheat_radius,
         };
 
-        inst.label_path();
-        inst
+        inst.label_path()?;
+        inst.render();
+
+        Ok(inst)
+    }
+
+    fn render(&self) {
+        if !render::enabled("day_20::render") {
+            return;
+        }
+
+        let renderer = Renderer::new(&self.track, |tile| match tile {
+            Tile::Wall => Glyph::new('#'),
+            Tile::Path(_) => Glyph::new('.'),
+        })
+        .cursor(self.start, Glyph::colored('S', Rgb::GREEN))
+        .cursor(self.end, Glyph::colored('E', Rgb::RED));
+
+        render::show("day_20::render", "day_20-race-track", &renderer);
     }
 
-    fn label_path(&mut self) {
+    fn label_path(&mut self) -> Result<()> {
         let mut distance = 0;
         let mut p = self.start;
 
@@ -91,28 +92,24 @@ impl RaceTrack {
 
         while p != self.end {
             distance += 1;
-            for n in p.neighbours() {
-                match self.track[n] {
-                    Tile::Wall => continue,
-                    Tile::Path(0) => {
-                        self.track[n] = Tile::Path(distance);
-                        p = n;
-                        break;
-                    }
-                    Tile::Path(d) if d > 0 => continue,
-                    _ => unreachable!(),
-                }
-            }
+            let (next, _) = self
+                .track
+                .neighbours4(p)
+                .find(|(_, tile)| matches!(tile, Tile::Path(0)))
+                .ok_or_else(|| {
+                    Error::NoSolution("The race track doesn't lead from S to E".to_string())
+                })?;
+
+            self.track[next] = Tile::Path(distance);
+            p = next;
         }
         self.track[self.start] = Tile::Path(0);
+
+        Ok(())
     }
 
     fn at(&self, pos: Vec2) -> Option<Tile> {
-        if pos.inside(&self.track.dims()) {
-            Some(self.track[pos])
-        } else {
-            None
-        }
+        self.track.get(pos).copied()
     }
 
     fn for_each_in_manhattan_circle(center: Vec2, radius: i64, mut f: impl FnMut(Vec2)) {
@@ -133,7 +130,7 @@ impl RaceTrack {
             if let Tile::Path(d0) = tile {
                 Self::for_each_in_manhattan_circle(p0, radius, |p1| {
                     if let Some(Tile::Path(d1)) = self.at(p1) {
-                        let shortcut = d1 - d0 - &p1.manhattan_dist(&p0);
+                        let shortcut = d1 - d0 - p1.manhattan_dist(&p0);
 
                         if shortcut >= threshold {
                             used_cheats.insert((p0, p1));
@@ -147,15 +144,67 @@ impl RaceTrack {
     }
 }
 
-pub fn run(input: Input, part: day::Part) -> Result<i64> {
-    let race_track = RaceTrack::from_input(input);
+pub struct Day;
+
+impl Solution for Day {
+    type Model = RaceTrack;
 
-    let result = match part {
-        day::Part::One => race_track.find_cheats(100, 2),
-        day::Part::Two => race_track.find_cheats(100, 20),
-    } as i64;
+    const DAY: u8 = 20;
+    const TITLE: &'static str = "Race Condition";
+    const PARAMS: &'static [&'static dyn ParamSpec] = &[&THRESHOLD, &RADIUS_ONE, &RADIUS_TWO];
+
+    fn parse(input: Input, params: &Params) -> Result<Self::Model> {
+        let cheat_radius = (params.get(&RADIUS_ONE)?, params.get(&RADIUS_TWO)?);
+        RaceTrack::from_input(input, params.get(&THRESHOLD)?, cheat_radius)
+    }
+
+    fn part_one(race_track: &Self::Model) -> Result<Answer> {
+        Ok(race_track
+            .find_cheats(race_track.threshold, race_track.cheat_radius.0)
+            .into())
+    }
 
-    Ok(result)
+    fn part_two(race_track: &Self::Model) -> Result<Answer> {
+        Ok(race_track
+            .find_cheats(race_track.threshold, race_track.cheat_radius.1)
+            .into())
+    }
+}
+
+#[cfg(test)]
+const EXAMPLE: &str = "\
+###############
+#...#...#.....#
+#.#.#.#.#.###.#
+#S#...#.#.#...#
+#######.#.#.###
+#######.#.#...#
+#######.#.###.#
+###..E#...#...#
+###.#######.###
+#...###...#...#
+#.#####.#.###.#
+#.#...#.#.#...#
+#.#.#.#.#.#.###
+#...#...#...###
+###############
+";
+
+example_tests! {
+    part_one: part_one(EXAMPLE, threshold = 64) => 1,
+    part_two: part_two(EXAMPLE, threshold = 76) => 3,
+}
+
+#[cfg(test)]
+mod test_errors {
+    use super::*;
+
+    #[test]
+    fn track_not_reaching_the_end() {
+        let result = Day::parse(Input::from_str("#####\n#S#E#\n#####\n"), &Params::new());
+
+        assert!(matches!(result, Err(Error::NoSolution(_))));
+    }
 }
 
 day_tests!("day_20-1.dat", 1406, 1006101);
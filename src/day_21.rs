@@ -1,11 +1,23 @@
 use std::collections::HashMap;
 use std::fmt::{self, Display, Formatter};
 
-use crate::error::Result;
+use crate::answer::Answer;
+use crate::error::{Error, ParseError, Result};
 use crate::input::Input;
+use crate::params::{Param, ParamSpec, Params};
+use crate::solution::Solution;
 use crate::util::grid::Grid;
-use crate::util::Vec2;
-use crate::{day, day_tests};
+use crate::util::parse::{self, Line};
+use crate::util::{Dir4, Vec2};
+use crate::{day_tests, example_tests};
+
+use log::trace;
+
+const DEPTH_ONE: Param<u8> = Param::new("depth_one", 2).at_least(1);
+const DEPTH_TWO: Param<u8> = Param::new("depth_two", 25).at_least(1);
+
+// every digit doubles the paths tried on the numeric keypad, longer keycodes would take forever
+const MAX_KEYCODE_DIGITS: usize = 10;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum Keypad {
@@ -13,72 +25,56 @@ enum Keypad {
     None,
 }
 
+/// A button of the directional keypad, one of the arrows or `A` to press the button the robot's
+/// arm points at.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
-enum Dir {
-    Up,
-    Down,
-    Left,
-    Right,
+enum Button {
+    Arrow(Dir4),
     A,
 }
 
-impl Display for Dir {
+impl Button {
+    const UP: Button = Button::Arrow(Dir4::Up);
+    const DOWN: Button = Button::Arrow(Dir4::Down);
+    const LEFT: Button = Button::Arrow(Dir4::Left);
+    const RIGHT: Button = Button::Arrow(Dir4::Right);
+}
+
+impl Display for Button {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(
             f,
             "{}",
             match self {
-                Self::Up => "↑",
-                Self::Down => "↓",
-                Self::Left => "←",
-                Self::Right => "→",
+                Self::Arrow(Dir4::Up) => "↑",
+                Self::Arrow(Dir4::Down) => "↓",
+                Self::Arrow(Dir4::Left) => "←",
+                Self::Arrow(Dir4::Right) => "→",
                 Self::A => "A",
             }
         )
     }
 }
 
-impl Dir {
-    fn to_int(&self) -> usize {
-        match self {
-            Self::Up => 0,
-            Self::Down => 1,
-            Self::Left => 2,
-            Self::Right => 3,
-            Self::A => 4,
+impl From<Button> for usize {
+    fn from(button: Button) -> Self {
+        match button {
+            Button::Arrow(dir) => dir.index(),
+            Button::A => 4,
         }
     }
 }
 
-impl<T> std::ops::Index<Dir> for Vec<T> {
-    type Output = T;
-
-    fn index(&self, index: Dir) -> &Self::Output {
-        &self[index.to_int()]
-    }
-}
-
-impl<T> std::ops::IndexMut<Dir> for Vec<T> {
-    fn index_mut(&mut self, index: Dir) -> &mut Self::Output {
-        &mut self[index.to_int()]
-    }
-}
-
-impl Into<usize> for Dir {
-    fn into(self) -> usize {
-        self.to_int()
-    }
-}
-
 type KeypadGrid = Grid<Vec2>;
-type DirTable = Grid<Vec<Dir>>;
+type DirTable = Grid<Vec<Button>>;
 
+#[derive(Clone)]
 struct KeypadTable {
     keypad: Grid<Keypad>,
     keypad_table: KeypadGrid,
 
     dir_table: DirTable,
-    cache: HashMap<(Dir, Dir, u8), u64>,
+    cache: HashMap<(Button, Button, u8), u64>,
 }
 
 impl KeypadTable {
@@ -105,7 +101,7 @@ impl KeypadTable {
                 let from_pos = self.key_pos(Keypad::Key(from));
                 let to_pos = self.key_pos(Keypad::Key(to));
 
-                self.keypad_table[(from, to)] = to_pos - &from_pos;
+                self.keypad_table[(from, to)] = to_pos - from_pos;
             }
         }
         self
@@ -121,65 +117,73 @@ impl KeypadTable {
     }
 
     fn init_handmade_dir_table() -> DirTable {
-        let mut table: Grid<Vec<Dir>> = Grid::with_size(5u8, 5u8, vec![]);
+        let mut table: Grid<Vec<Button>> = Grid::with_size(5u8, 5u8, vec![]);
 
-        table[(Dir::A, Dir::Up)] = vec![Dir::Left];
-        table[(Dir::A, Dir::Left)] = vec![Dir::Down, Dir::Left, Dir::Left];
-        table[(Dir::A, Dir::Right)] = vec![Dir::Down];
-        table[(Dir::A, Dir::Down)] = vec![Dir::Left, Dir::Down];
+        table[(Button::A, Button::UP)] = vec![Button::LEFT];
+        table[(Button::A, Button::LEFT)] = vec![Button::DOWN, Button::LEFT, Button::LEFT];
+        table[(Button::A, Button::RIGHT)] = vec![Button::DOWN];
+        table[(Button::A, Button::DOWN)] = vec![Button::LEFT, Button::DOWN];
 
-        table[(Dir::Up, Dir::A)] = vec![Dir::Right];
-        table[(Dir::Up, Dir::Left)] = vec![Dir::Down, Dir::Left];
-        table[(Dir::Up, Dir::Right)] = vec![Dir::Down, Dir::Right];
-        table[(Dir::Up, Dir::Down)] = vec![Dir::Down];
+        table[(Button::UP, Button::A)] = vec![Button::RIGHT];
+        table[(Button::UP, Button::LEFT)] = vec![Button::DOWN, Button::LEFT];
+        table[(Button::UP, Button::RIGHT)] = vec![Button::DOWN, Button::RIGHT];
+        table[(Button::UP, Button::DOWN)] = vec![Button::DOWN];
 
-        table[(Dir::Left, Dir::A)] = vec![Dir::Right, Dir::Right, Dir::Up];
-        table[(Dir::Left, Dir::Up)] = vec![Dir::Right, Dir::Up];
-        table[(Dir::Left, Dir::Right)] = vec![Dir::Right, Dir::Right];
-        table[(Dir::Left, Dir::Down)] = vec![Dir::Right];
+        table[(Button::LEFT, Button::A)] = vec![Button::RIGHT, Button::RIGHT, Button::UP];
+        table[(Button::LEFT, Button::UP)] = vec![Button::RIGHT, Button::UP];
+        table[(Button::LEFT, Button::RIGHT)] = vec![Button::RIGHT, Button::RIGHT];
+        table[(Button::LEFT, Button::DOWN)] = vec![Button::RIGHT];
 
-        table[(Dir::Right, Dir::A)] = vec![Dir::Up];
-        table[(Dir::Right, Dir::Up)] = vec![Dir::Left, Dir::Up];
-        table[(Dir::Right, Dir::Left)] = vec![Dir::Left, Dir::Left];
-        table[(Dir::Right, Dir::Down)] = vec![Dir::Left];
+        table[(Button::RIGHT, Button::A)] = vec![Button::UP];
+        table[(Button::RIGHT, Button::UP)] = vec![Button::LEFT, Button::UP];
+        table[(Button::RIGHT, Button::LEFT)] = vec![Button::LEFT, Button::LEFT];
+        table[(Button::RIGHT, Button::DOWN)] = vec![Button::LEFT];
 
-        table[(Dir::Down, Dir::A)] = vec![Dir::Up, Dir::Right];
-        table[(Dir::Down, Dir::Up)] = vec![Dir::Up];
-        table[(Dir::Down, Dir::Left)] = vec![Dir::Left];
-        table[(Dir::Down, Dir::Right)] = vec![Dir::Right];
+        table[(Button::DOWN, Button::A)] = vec![Button::UP, Button::RIGHT];
+        table[(Button::DOWN, Button::UP)] = vec![Button::UP];
+        table[(Button::DOWN, Button::LEFT)] = vec![Button::LEFT];
+        table[(Button::DOWN, Button::RIGHT)] = vec![Button::RIGHT];
 
         for i in 0..table.height() {
             for j in 0..table.width() {
-                table[(i, j)].push(Dir::A);
+                table[(i, j)].push(Button::A);
             }
         }
 
         table
     }
 
-    fn push_n_arrows(v: &mut Vec<Dir>, arrow: Dir, n: i64) {
+    fn push_n_arrows(v: &mut Vec<Button>, arrow: Button, n: i64) {
         for _ in 0..n {
             v.push(arrow);
         }
     }
 
-    fn move_vertical(v: &mut Vec<Dir>, pos: Vec2) {
-        Self::push_n_arrows(v, if pos.y > 0 { Dir::Down } else { Dir::Up }, pos.y.abs());
+    fn move_vertical(v: &mut Vec<Button>, pos: Vec2) {
+        Self::push_n_arrows(
+            v,
+            if pos.y > 0 { Button::DOWN } else { Button::UP },
+            pos.y.abs(),
+        );
     }
 
-    fn move_horizontal(v: &mut Vec<Dir>, pos: Vec2) {
+    fn move_horizontal(v: &mut Vec<Button>, pos: Vec2) {
         Self::push_n_arrows(
             v,
-            if pos.x > 0 { Dir::Right } else { Dir::Left },
+            if pos.x > 0 {
+                Button::RIGHT
+            } else {
+                Button::LEFT
+            },
             pos.x.abs(),
         );
     }
 
-    fn moves_at_level(&mut self, path: &Vec<Dir>, depth: u8) -> u64 {
+    fn moves_at_level(&mut self, path: &Vec<Button>, depth: u8) -> u64 {
         self.cache.clear();
 
         let mut total = 0;
-        let mut prev = Dir::A;
+        let mut prev = Button::A;
         for dir in path {
             let moves = self.count_moves(prev, *dir, depth - 1);
             prev = *dir;
@@ -192,7 +196,7 @@ impl KeypadTable {
         &mut self,
         at: Keypad,
         remaining: &[Keypad],
-        path: &mut Vec<Dir>,
+        path: &mut Vec<Button>,
         depth: u8,
     ) -> u64 {
         if remaining.is_empty() {
@@ -214,7 +218,7 @@ impl KeypadTable {
                 let mut path1 = path.clone();
                 Self::move_vertical(&mut path1, path_vec);
                 Self::move_horizontal(&mut path1, path_vec);
-                path1.push(Dir::A);
+                path1.push(Button::A);
                 self.keypad_path(*first, tail, &mut path1, depth)
             };
 
@@ -224,7 +228,7 @@ impl KeypadTable {
                 let mut path2 = path.clone();
                 Self::move_horizontal(&mut path2, path_vec);
                 Self::move_vertical(&mut path2, path_vec);
-                path2.push(Dir::A);
+                path2.push(Button::A);
                 self.keypad_path(*first, tail, &mut path2, depth)
             };
 
@@ -234,7 +238,7 @@ impl KeypadTable {
         }
     }
 
-    fn count_moves(&mut self, from: Dir, to: Dir, level: u8) -> u64 {
+    fn count_moves(&mut self, from: Button, to: Button, level: u8) -> u64 {
         let path = &self.dir_table[(from, to)];
 
         if level == 0 {
@@ -242,7 +246,7 @@ impl KeypadTable {
         }
 
         let mut total = 0;
-        let mut prev = Dir::A;
+        let mut prev = Button::A;
 
         // TODO: don't clone here
         for dir in path.clone() {
@@ -261,16 +265,44 @@ impl KeypadTable {
     }
 }
 
-struct KeypadConundrum {
+#[derive(Clone)]
+pub struct KeypadConundrum {
     keypad: KeypadTable,
-    kecodes: Vec<String>,
+    kecodes: Vec<(String, u64)>, // keycodes and their numeric parts
+    depths: (u8, u8),            // robots between you and the door in part one and two
 }
 
 impl KeypadConundrum {
-    pub fn new(input: Input) -> Self {
-        Self {
+    pub fn new(mut input: Input, depths: (u8, u8)) -> Result<Self> {
+        let kecodes = parse::lines(&mut input)
+            .map(|line| Self::read_keycode(&line))
+            .collect::<Result<_, ParseError>>()?;
+
+        Ok(Self {
             keypad: KeypadTable::new(),
-            kecodes: input.lines().map(|l| l.unwrap()).collect(),
+            kecodes,
+            depths,
+        })
+    }
+
+    fn read_keycode(line: &Line) -> Result<(String, u64), ParseError> {
+        let expected = "a keycode of digits ending with A, e.g. 029A";
+
+        let Some(digits) = line.strip_suffix('A') else {
+            return Err(line.line_error(expected));
+        };
+
+        match digits.find(|c: char| !c.is_ascii_digit()) {
+            _ if digits.is_empty() => Err(line.line_error(expected)),
+            Some(i) => Err(line.error(&digits[i..i + 1], expected)),
+            None if digits.len() > MAX_KEYCODE_DIGITS => Err(line.error(
+                digits,
+                format!("a keycode of at most {MAX_KEYCODE_DIGITS} digits"),
+            )),
+            None => match digits.parse() {
+                Ok(value) => Ok((line.text.clone(), value)),
+                Err(_) => Err(line.line_error(expected)),
+            },
         }
     }
 
@@ -284,11 +316,8 @@ impl KeypadConundrum {
             .collect()
     }
 
-    fn moves_for_keycode(&mut self, keycode: &str, depth: u8) -> u64 {
-        let keycode_value = keycode[0..keycode.len() - 1]
-            .parse::<u64>()
-            .expect("numeric keycode");
-
+    // the complexity of the keycode, none if it overflows
+    fn moves_for_keycode(&mut self, keycode: &str, keycode_value: u64, depth: u8) -> Option<u64> {
         let keycode = self.decode_keycode(keycode);
 
         let mut path = Vec::new();
@@ -296,29 +325,91 @@ impl KeypadConundrum {
             .keypad
             .keypad_path(Keypad::Key(0xA), &keycode, &mut path, depth);
 
-        println!("{cost} * {keycode_value} = {}", cost * keycode_value);
+        let complexity = keycode_value.checked_mul(cost);
+        trace!(target: "day_21::trace", "{cost} * {keycode_value} = {complexity:?}");
 
-        keycode_value * cost
+        complexity
     }
 
-    fn count_moves(&mut self, depth: u8) -> u64 {
+    fn count_moves(&mut self, depth: u8) -> Result<u64> {
         self.kecodes
             .clone()
             .iter()
-            .map(|keycode| self.moves_for_keycode(keycode, depth))
-            .sum()
+            .try_fold(0_u64, |total, (keycode, value)| {
+                self.moves_for_keycode(keycode, *value, depth)
+                    .and_then(|complexity| total.checked_add(complexity))
+                    .ok_or_else(|| {
+                        Error::NoSolution("The sum of complexities overflows 64 bits".to_string())
+                    })
+            })
+    }
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    type Model = KeypadConundrum;
+
+    const DAY: u8 = 21;
+    const TITLE: &'static str = "Keypad Conundrum";
+    const PARAMS: &'static [&'static dyn ParamSpec] = &[&DEPTH_ONE, &DEPTH_TWO];
+
+    fn parse(input: Input, params: &Params) -> Result<Self::Model> {
+        let depths = (params.get(&DEPTH_ONE)?, params.get(&DEPTH_TWO)?);
+        KeypadConundrum::new(input, depths)
+    }
+
+    fn part_one(keypad: &Self::Model) -> Result<Answer> {
+        let mut keypad = keypad.clone();
+
+        Ok(keypad.count_moves(keypad.depths.0)?.into())
+    }
+
+    fn part_two(keypad: &Self::Model) -> Result<Answer> {
+        let mut keypad = keypad.clone();
+
+        Ok(keypad.count_moves(keypad.depths.1)?.into())
     }
 }
 
-pub fn run(input: Input, part: day::Part) -> Result<i64> {
-    let mut keypad = KeypadConundrum::new(input);
+#[cfg(test)]
+const EXAMPLE: &str = "\
+029A
+980A
+179A
+456A
+379A
+";
+
+example_tests! {
+    part_one: part_one(EXAMPLE) => 126384,
+}
+
+#[cfg(test)]
+mod test_errors {
+    use super::*;
 
-    let result = match part {
-        day::Part::One => keypad.count_moves(2),
-        day::Part::Two => keypad.count_moves(25),
-    } as i64;
+    fn parse(input: &str) -> Result<KeypadConundrum> {
+        Day::parse(Input::from_str(input), &Params::new())
+    }
 
-    Ok(result)
+    #[test]
+    fn keycode_too_long() {
+        let Err(Error::Parse(error)) = parse("029A\n99999999999999999999A\n") else {
+            panic!("expected a parse error");
+        };
+
+        assert_eq!((error.line, error.column), (2, 1));
+        assert_eq!(error.expected, "a keycode of at most 10 digits");
+    }
+
+    #[test]
+    fn complexity_overflow() {
+        let keypad = parse("9999999999A\n").unwrap();
+
+        assert!(Day::part_one(&keypad).is_ok());
+        assert!(matches!(Day::part_two(&keypad), Err(Error::NoSolution(_))));
+    }
 }
 
 day_tests!("day_21-1.dat", 231564, 281212077733592);
@@ -1,8 +1,16 @@
 use std::collections::{HashMap, HashSet};
 
-use crate::error::Result;
+use crate::answer::Answer;
+use crate::error::{ParseError, Result};
 use crate::input::Input;
-use crate::{day, day_tests};
+use crate::params::{Param, ParamSpec, Params};
+use crate::solution::Solution;
+use crate::util::{parallel, parse};
+use crate::{day_tests, example_tests};
+
+use log::info;
+
+const SECRETS: Param<u64> = Param::new("secrets", 2000);
 
 struct SecretGenerator {
     value: u64,
@@ -19,17 +27,17 @@ impl SecretGenerator {
 
     fn next_bare(&self, mut value: u64) -> u64 {
         let mut tmp = value * 64; // shift left by 6
-        tmp = value ^ tmp;
+        tmp ^= value;
         let new_secret = tmp % self.modulo;
         value = new_secret;
 
         tmp = value / 32;
-        tmp = value ^ tmp;
+        tmp ^= value;
         let next_secret = tmp % self.modulo;
         value = next_secret;
 
         tmp = value * 2048;
-        tmp = value ^ tmp;
+        tmp ^= value;
         let final_secret = tmp % self.modulo;
         value = final_secret;
 
@@ -109,29 +117,32 @@ impl MonkeyBroker {
     }
 }
 
-struct MonkeyStockExchange {
+pub struct MonkeyStockExchange {
     iterations: u64,
     brokers: Vec<MonkeyBroker>,
 }
 
 impl MonkeyStockExchange {
-    fn new(input: Input, n: u64) -> Self {
-        let brokers = input
-            .lines()
-            .map(|line| line.expect("valid input").parse::<u64>().expect("a number"))
-            .map(|seed| MonkeyBroker::new(seed, n))
-            .collect::<Vec<_>>();
-        Self {
+    fn new(mut input: Input, n: u64) -> Result<Self> {
+        let seeds = parse::lines(&mut input)
+            .filter(|line| !line.is_blank())
+            .map(|line| {
+                line.trim()
+                    .parse::<u64>()
+                    .map_err(|_| line.line_error("a secret number"))
+            })
+            .collect::<Result<Vec<_>, ParseError>>()?;
+
+        Ok(Self {
             iterations: n,
-            brokers,
-        }
+            brokers: parallel::map(&seeds, |&seed| MonkeyBroker::new(seed, n)),
+        })
     }
 
     // Task #1
     fn find_secrets(&self) -> u64 {
-        self.brokers
-            .iter()
-            .map(|broker| broker.nth_secret(self.iterations))
+        parallel::map(&self.brokers, |broker| broker.nth_secret(self.iterations))
+            .into_iter()
             .sum()
     }
 
@@ -147,37 +158,115 @@ impl MonkeyStockExchange {
         let mut best_sell_price = 0;
         let mut checked_seq_cache = HashSet::new();
 
-        for (_i, broker) in self.brokers.iter().enumerate() {
-            for seq in broker.sell_prices.keys() {
-                if !checked_seq_cache.insert(*seq) {
-                    continue;
-                }
-
-                let sell_price = self.seq_sell_price(seq);
-                if sell_price > best_sell_price {
-                    best_sell_price = sell_price;
-                    best_sell_sequence = *seq;
-                }
+        let sequences = self
+            .brokers
+            .iter()
+            .flat_map(|broker| broker.sell_prices.keys())
+            .filter(|seq| checked_seq_cache.insert(**seq))
+            .copied()
+            .collect::<Vec<_>>();
+        let sell_prices = parallel::map(&sequences, |seq| self.seq_sell_price(seq));
+
+        for (seq, sell_price) in sequences.into_iter().zip(sell_prices) {
+            if sell_price > best_sell_price {
+                best_sell_price = sell_price;
+                best_sell_sequence = seq;
             }
         }
 
-        println!("best sell sequence: {:?}", best_sell_sequence);
+        info!(target: "day_22::progress", "best sell sequence: {:?}", best_sell_sequence);
         let sell_price = self.seq_sell_price(&best_sell_sequence);
 
         sell_price as u64
     }
 }
 
-pub fn run(input: Input, part: day::Part) -> Result<i64> {
-    let iterations = 2000;
-    let mse = MonkeyStockExchange::new(input, iterations);
+pub struct Day;
+
+impl Solution for Day {
+    type Model = MonkeyStockExchange;
+
+    const DAY: u8 = 22;
+    const TITLE: &'static str = "Monkey Market";
+    const PARAMS: &'static [&'static dyn ParamSpec] = &[&SECRETS];
 
-    let result = match part {
-        day::Part::One => mse.find_secrets(),
-        day::Part::Two => mse.find_sell_sequence(),
-    } as i64;
+    fn parse(input: Input, params: &Params) -> Result<Self::Model> {
+        MonkeyStockExchange::new(input, params.get(&SECRETS)?)
+    }
+
+    fn part_one(mse: &Self::Model) -> Result<Answer> {
+        Ok(mse.find_secrets().into())
+    }
 
-    Ok(result)
+    fn part_two(mse: &Self::Model) -> Result<Answer> {
+        Ok(mse.find_sell_sequence().into())
+    }
+}
+
+#[cfg(test)]
+const EXAMPLE: &str = "\
+1
+10
+100
+2024
+";
+
+#[cfg(test)]
+const SELL_EXAMPLE: &str = "\
+1
+2
+3
+2024
+";
+
+example_tests! {
+    part_one: part_one(EXAMPLE) => 37327623,
+    part_one_ten_secrets: part_one("123\n", secrets = 10) => 5908254,
+    part_two: part_two(SELL_EXAMPLE) => 23,
+}
+
+#[cfg(test)]
+mod test_errors {
+    use super::*;
+    use crate::error::Error;
+
+    #[test]
+    fn blank_lines_skipped() {
+        let exchange = Day::parse(Input::from_str("1\n\n10\n100\n2024\n\n"), &Params::new());
+
+        assert_eq!(Day::part_one(&exchange.unwrap()).unwrap(), 37327623);
+    }
+
+    #[test]
+    fn secret_numbers_only() {
+        let result = Day::parse(Input::from_str("1\n\n-10\n"), &Params::new());
+
+        assert!(matches!(result, Err(Error::Parse(e)) if e.line == 3 && e.text == "-10"));
+    }
+}
+
+#[cfg(test)]
+mod test_parallel {
+    use super::*;
+    use crate::day::Part;
+    use crate::solution::DynSolution;
+    use crate::util::parallel::test::with_threads;
+
+    fn run(threads: usize, example: &str, part: Part) -> Answer {
+        with_threads(threads, || {
+            Day.run(Input::from_str(example), &Params::new(), part)
+                .unwrap()
+        })
+    }
+
+    #[test]
+    fn same_answers_on_any_thread_count() {
+        assert_eq!(run(4, EXAMPLE, Part::One), run(1, EXAMPLE, Part::One));
+        assert_eq!(
+            run(4, SELL_EXAMPLE, Part::Two),
+            run(1, SELL_EXAMPLE, Part::Two)
+        );
+    }
 }
 
 day_tests!("day_22-1.dat", 15608699004, 1791);
@@ -1,44 +1,67 @@
 use std::collections::{HashMap, HashSet};
 
+use crate::answer::Answer;
 use crate::day_8::SubsetGenerator;
-use crate::error::Result;
+use crate::error::{ParseError, Result};
 use crate::input::Input;
+use crate::params::Params;
+use crate::solution::Solution;
 use crate::util::grid::Grid;
-use crate::{day, day_tests};
+use crate::{day_tests, example_tests};
+
+use log::info;
 
 type Node = u16;
 
-struct Network {
+pub struct Network {
     edges: HashMap<Node, Vec<Node>>, // adjacency list
     matrix: Grid<bool>,              // incidence matrix for quick edges lookup
 }
 
 impl Network {
-    fn new(input: Input) -> Self {
+    fn new(input: Input) -> Result<Self> {
         let max_nodes: usize = 26 * 26;
 
         let mut network_matrix = Grid::<bool>::with_size(max_nodes, max_nodes, false);
         let mut network_edges = HashMap::<Node, Vec<Node>>::new();
 
-        input.lines().for_each(|line| {
-            let line = line.expect("valid input");
-            let mut parts = line.trim_end().split("-");
+        for (i, line) in input.lines().enumerate() {
+            let line = line?;
+            let line = line.trim_end();
+            if line.is_empty() {
+                continue;
+            }
 
-            if let (Some(a), Some(b)) = (parts.next(), parts.next()) {
-                let a = Self::label_to_id(a);
-                let b = Self::label_to_id(b);
+            let Some((a, b)) = line.split_once("-") else {
+                return Err(ParseError::new(i + 1, line, "a connection like ab-cd").into());
+            };
 
-                network_edges.entry(a).or_insert_with(|| vec![]).push(b);
-                network_edges.entry(b).or_insert_with(|| vec![]).push(a);
+            let a = Self::parse_label(i + 1, line, a)?;
+            let b = Self::parse_label(i + 1, line, b)?;
 
-                network_matrix[(a, b)] = true;
-                network_matrix[(b, a)] = true;
-            }
-        });
+            network_edges.entry(a).or_default().push(b);
+            network_edges.entry(b).or_default().push(a);
+
+            network_matrix[(a, b)] = true;
+            network_matrix[(b, a)] = true;
+        }
 
-        Self {
+        Ok(Self {
             matrix: network_matrix,
             edges: network_edges,
+        })
+    }
+
+    fn parse_label(line_no: usize, line: &str, label: &str) -> Result<Node, ParseError> {
+        if label.len() == 2 && label.bytes().all(|c| c.is_ascii_lowercase()) {
+            Ok(Self::label_to_id(label))
+        } else {
+            Err(ParseError::at(
+                line_no,
+                line,
+                label,
+                "a computer name of two lowercase letters",
+            ))
         }
     }
 
@@ -83,7 +106,7 @@ impl Network {
         for (u, u_edges) in &self.edges {
             let mut pairs_gen = SubsetGenerator::new(2, u_edges.len());
 
-            while let Some(pair) = pairs_gen.next() {
+            while let Some(pair) = pairs_gen.next_subset() {
                 let vertices = pair.iter().map(|x| u_edges[*x]).collect::<Vec<_>>();
                 if self.is_clique(&vertices) {
                     let mut _3_clique = vertices;
@@ -114,7 +137,7 @@ impl Network {
                 // generate all k-1 subsets of U's edges, and check if, together with U, they form a clique
                 let mut gen = SubsetGenerator::new(k - 1, u_edges.len());
 
-                while let Some(subset) = gen.next() {
+                while let Some(subset) = gen.next_subset() {
                     let mut vertices = subset.iter().map(|x| u_edges[*x]).collect::<Vec<_>>();
 
                     if self.is_clique(&vertices) {
@@ -129,7 +152,12 @@ impl Network {
             }
 
             if has_better_result {
-                println!("found a clique of size {}, trying {}...", k, k + 1);
+                info!(
+                    target: "day_23::progress",
+                    "found a clique of size {}, trying {}...",
+                    k,
+                    k + 1
+                );
                 k += 1;
             } else {
                 break;
@@ -147,33 +175,82 @@ impl Network {
     }
 
     // Task #2
-    fn find_largest_party(&self) -> usize {
+    fn find_largest_party(&self) -> String {
         let mut max_clique = self.find_max_clique();
         max_clique.sort();
 
-        print!("password:");
-        for node in &max_clique {
-            print!("{},", Self::label_from_id(*node));
-        }
-        println!();
-
-        max_clique.len()
+        max_clique
+            .iter()
+            .map(|node| Self::label_from_id(*node))
+            .collect::<Vec<_>>()
+            .join(",")
     }
 }
 
-pub fn run(input: Input, part: day::Part) -> Result<i64> {
-    let network = Network::new(input);
+pub struct Day;
+
+impl Solution for Day {
+    type Model = Network;
+
+    const DAY: u8 = 23;
+    const TITLE: &'static str = "LAN Party";
+
+    fn parse(input: Input, _: &Params) -> Result<Self::Model> {
+        Network::new(input)
+    }
 
-    let result = match part {
-        day::Part::One => network.find_lan_parties(),
-        day::Part::Two => network.find_largest_party(),
-    } as i64;
+    fn part_one(network: &Self::Model) -> Result<Answer> {
+        Ok(network.find_lan_parties().into())
+    }
+
+    fn part_two(network: &Self::Model) -> Result<Answer> {
+        Ok(network.find_largest_party().into())
+    }
+}
 
-    Ok(result)
+#[cfg(test)]
+const EXAMPLE: &str = "\
+kh-tc
+qp-kh
+de-cg
+ka-co
+yn-aq
+qp-ub
+cg-tb
+vc-aq
+tb-ka
+wh-tc
+yn-cg
+kh-ub
+ta-co
+de-co
+tc-td
+tb-wq
+wh-td
+ta-ka
+td-qp
+aq-cg
+wq-ub
+ub-vc
+de-ta
+wq-aq
+wq-vc
+wh-yn
+ka-de
+kh-ta
+co-tc
+wh-qp
+tb-vc
+td-yn
+";
+
+example_tests! {
+    part_one: part_one(EXAMPLE) => 7,
+    part_two: part_two(EXAMPLE) => "co,de,ka,ta",
 }
 
 day_tests!(
     "day_23-1.dat",
     1163,
-    13 /* 'bm,bo,ee,fo,gt,hv,jv,kd,md,mu,nm,wx,xh' */
+    "bm,bo,ee,fo,gt,hv,jv,kd,md,mu,nm,wx,xh"
 );
@@ -1,10 +1,13 @@
 use std::collections::{HashMap, HashSet};
 use std::fmt::{self, Debug};
-use std::hash::{DefaultHasher, Hash, Hasher};
 
-use crate::error::Result;
+use crate::answer::Answer;
+use crate::error::{ParseError, Result};
 use crate::input::Input;
-use crate::{day, day_tests};
+use crate::params::Params;
+use crate::solution::Solution;
+use crate::util::parse::{self, Line};
+use crate::{day_tests, example_tests};
 
 use log::info;
 
@@ -66,47 +69,49 @@ impl InputReader {
         Self { input }
     }
 
-    fn read_input_wires(&mut self) -> Values {
-        let mut values = HashMap::new();
-        while let Some(line) = self.input.read_line() {
-            if line == "\n" {
-                break;
-            }
-            let mut parts = line.split(": ");
+    fn read_label(line: &Line, label: &str) -> Result<WireLabel, ParseError> {
+        label
+            .as_bytes()
+            .try_into()
+            .map_err(|_| line.error(label, "a wire label of 3 characters"))
+    }
 
-            let label = parts.next().expect("wire label");
-            let value = parts.next().expect("wire value").trim_end();
+    fn read_input_wires(&mut self) -> Result<Values> {
+        let mut values = HashMap::new();
+        for line in parse::read_section(&mut self.input).unwrap_or_default() {
+            let (label, value) = line.split_once(": ", "an input wire like x00: 1")?;
 
             let value = match value {
                 "1" => true,
                 "0" => false,
-                _ => unreachable!("boolean value"),
+                _ => return Err(line.error(value, "a wire value 0 or 1").into()),
             };
 
-            values.insert(parse_label(label), value);
+            values.insert(Self::read_label(&line, label)?, value);
         }
-        values
+        Ok(values)
     }
 
-    fn read_gates(self) -> (Vec<Gate>, Wires) {
+    fn read_gates(&mut self) -> Result<(Vec<Gate>, Wires)> {
         let mut output_wires = Wires::new();
-        let gates = self
-            .input
-            .lines()
+        let gates = parse::read_section(&mut self.input)
+            .unwrap_or_default()
+            .iter()
             .map(|line| {
-                let line = line.unwrap();
-                let mut parts = line.split(" ");
+                let parts: Vec<_> = line.split(" ").collect();
+                let &[left, gate, right, "->", output] = parts.as_slice() else {
+                    return Err(line.line_error("a gate like x00 AND y00 -> z00").into());
+                };
 
-                let left = parse_label(parts.next().expect("input #1"));
-                let gate = parts.next().expect("gate");
-                let right = parse_label(parts.next().expect("input #2"));
-                let output = parse_label(parts.skip(1).next().expect("output"));
+                let left = Self::read_label(line, left)?;
+                let right = Self::read_label(line, right)?;
+                let output = Self::read_label(line, output)?;
 
                 if output[0] == b'z' && !output_wires.contains(&output) {
                     output_wires.insert(output);
                 }
 
-                Gate {
+                Ok(Gate {
                     left,
                     right,
                     output,
@@ -114,12 +119,12 @@ impl InputReader {
                         "AND" => GateType::And,
                         "OR" => GateType::Or,
                         "XOR" => GateType::Xor,
-                        _ => unimplemented!("unknown gate type"),
+                        _ => return Err(line.error(gate, "AND, OR or XOR").into()),
                     },
-                }
+                })
             })
-            .collect();
-        (gates, output_wires)
+            .collect::<Result<_>>()?;
+        Ok((gates, output_wires))
     }
 }
 
@@ -135,7 +140,8 @@ enum CircuitResult {
     NotFound,
 }
 
-struct CrossedWires {
+#[derive(Clone)]
+pub struct CrossedWires {
     values: Values,
     gates: Vec<Gate>,
     output_wires: Wires,
@@ -144,32 +150,28 @@ struct CrossedWires {
 }
 
 impl CrossedWires {
-    fn from_input(input: Input) -> Self {
+    fn from_input(input: Input) -> Result<Self> {
         let mut reader = InputReader::new(input);
-        let values = reader.read_input_wires();
-        let (gates, output_wires) = reader.read_gates();
+        let values = reader.read_input_wires()?;
+        let (gates, output_wires) = reader.read_gates()?;
 
-        Self {
+        Ok(Self {
             original_values: values.clone(),
             values,
             gates,
             output_wires,
-        }
+        })
     }
 
     fn find_gates(&self, filter: impl Fn(&Gate) -> bool) -> Vec<Gate> {
-        self.gates
-            .iter()
-            .filter(|g| filter(*g))
-            .map(|gate| *gate)
-            .collect()
+        self.gates.iter().filter(|g| filter(g)).copied().collect()
     }
 
     fn try_find_gate(&self, output: WireLabel) -> Option<Gate> {
         self.gates
             .iter()
             .find(|&gate| gate.output == output)
-            .map(|gate| *gate)
+            .copied()
     }
 
     fn gate_with_output(&self, output: WireLabel) -> Gate {
@@ -178,11 +180,10 @@ impl CrossedWires {
 
     fn decode_variable(&self, prefix: &str) -> u64 {
         let result = (0..)
-            .into_iter()
             .map_while(|i| {
                 let wire = format!("{}{:02}", prefix, i);
 
-                self.values.get(&parse_label(&wire)).map(|&value| value)
+                self.values.get(&parse_label(&wire)).copied()
             })
             .fold((0, 1), |(acc, mask), value| {
                 let acc = acc + mask * value as u64;
@@ -230,7 +231,7 @@ impl CrossedWires {
 
     fn reset_values(&mut self) {
         self.values = self.original_values.clone();
-        for (_, value) in &mut self.values {
+        for value in self.values.values_mut() {
             *value = false;
         }
     }
@@ -303,12 +304,10 @@ impl CrossedWires {
 
                 self.is_carry_circuit(carry_next.output, n - 1)
             }
-            _ => {
-                return CircuitResult::Invalid(WiringError {
-                    gate: Some(gate),
-                    expected_op: GateType::Or,
-                });
-            }
+            _ => CircuitResult::Invalid(WiringError {
+                gate: Some(gate),
+                expected_op: GateType::Or,
+            }),
         }
     }
 
@@ -326,7 +325,7 @@ impl CrossedWires {
             });
         }
 
-        let inputs = self.find_gates(|g| (g.output == gate.left || g.output == gate.right));
+        let inputs = self.find_gates(|g| g.output == gate.left || g.output == gate.right);
 
         let or_gate = match inputs.iter().find(|g| g.op == GateType::Or) {
             Some(gate) => gate,
@@ -365,8 +364,7 @@ impl CrossedWires {
 
     fn max_bit_for_var(&self, prefix: &str) -> u8 {
         let cnt = (0..)
-            .into_iter()
-            .take_while(|i| self.values.contains_key(&label_from_u8(&prefix, *i)))
+            .take_while(|i| self.values.contains_key(&label_from_u8(prefix, *i)))
             .count();
         (cnt - 1) as u8
     }
@@ -413,12 +411,8 @@ impl CrossedWires {
                     })
                     .as_slice()
                 {
-                    [swap_with] => {
-                        return Some((gate.output, swap_with.output));
-                    }
-                    _ => {
-                        return None;
-                    }
+                    [swap_with] => Some((gate.output, swap_with.output)),
+                    _ => None,
                 }
             }
             _ => None,
@@ -439,21 +433,18 @@ impl CrossedWires {
     }
 
     fn check_and_fix(&self) -> Option<(WireLabel, WireLabel)> {
-        match self.validate_adder(2) {
-            (CircuitResult::Invalid(error), n) => {
-                let hint1 = &error;
-                // try running further to get a hint
-                if let (CircuitResult::Invalid(hint2), n2) = self.validate_adder(n + 1) {
-                    if n2 == n + 1 {
-                        return self.find_fix_with_hint(hint1, &hint2);
-                    }
-                }
-                // try direct replacement
-                if let Some(fix) = self.find_fix(&error) {
-                    return Some(fix);
+        if let (CircuitResult::Invalid(error), n) = self.validate_adder(2) {
+            let hint1 = &error;
+            // try running further to get a hint
+            if let (CircuitResult::Invalid(hint2), n2) = self.validate_adder(n + 1) {
+                if n2 == n + 1 {
+                    return self.find_fix_with_hint(hint1, &hint2);
                 }
             }
-            _ => {}
+            // try direct replacement
+            if let Some(fix) = self.find_fix(&error) {
+                return Some(fix);
+            }
         };
         None
     }
@@ -462,13 +453,14 @@ impl CrossedWires {
     // this monstrostiy that checks whether the circuit is a binary adder, reporting unexpected
     // gates (disclaimer: this is not a generalized solution, there are cicuits that do adding but
     // don't fit expected schema)
-    fn find_crossed_wires(&mut self) -> u64 {
+    fn find_crossed_wires(&mut self) -> String {
         self.reset_values();
         self.eval();
 
         let mut output_swaps = vec![];
         while let Some((from, to)) = self.check_and_fix() {
             info!(
+                target: "day_24::progress",
                 "found FIX swapping {:?} with {:?}",
                 print_label(&from),
                 print_label(&to)
@@ -479,33 +471,111 @@ impl CrossedWires {
 
         let mut crossed_wires = output_swaps
             .into_iter()
-            .map(|(a, b)| [print_label(&a), print_label(&b)])
-            .flatten()
+            .flat_map(|(a, b)| [print_label(&a), print_label(&b)])
             .collect::<Vec<_>>();
         crossed_wires.sort();
 
-        // formatted task solution
-        println!("{}", crossed_wires.join(","));
-
-        let mut hasher = DefaultHasher::new();
-        crossed_wires.hash(&mut hasher);
-        hasher.finish()
+        crossed_wires.join(",")
     }
 }
 
-pub fn run(input: Input, part: day::Part) -> Result<i64> {
-    let mut wires = CrossedWires::from_input(input);
+pub struct Day;
+
+impl Solution for Day {
+    type Model = CrossedWires;
+
+    const DAY: u8 = 24;
+    const TITLE: &'static str = "Crossed Wires";
 
-    let result = match part {
-        day::Part::One => wires.find_z_value(),
-        day::Part::Two => wires.find_crossed_wires(),
-    } as i64;
+    fn parse(input: Input, _: &Params) -> Result<Self::Model> {
+        CrossedWires::from_input(input)
+    }
+
+    fn part_one(wires: &Self::Model) -> Result<Answer> {
+        let mut wires = wires.clone();
+
+        Ok(wires.find_z_value().into())
+    }
+
+    fn part_two(wires: &Self::Model) -> Result<Answer> {
+        let mut wires = wires.clone();
+
+        Ok(wires.find_crossed_wires().into())
+    }
+}
 
-    Ok(result)
+#[cfg(test)]
+const SMALL_EXAMPLE: &str = "\
+x00: 1
+x01: 1
+x02: 1
+y00: 0
+y01: 1
+y02: 0
+
+x00 AND y00 -> z00
+x01 XOR y01 -> z01
+x02 OR y02 -> z02
+";
+
+#[cfg(test)]
+const EXAMPLE: &str = "\
+x00: 1
+x01: 0
+x02: 1
+x03: 1
+x04: 0
+y00: 1
+y01: 1
+y02: 1
+y03: 1
+y04: 1
+
+ntg XOR fgs -> mjb
+y02 OR x01 -> tnw
+kwq OR kpj -> z05
+x00 OR x03 -> fst
+tgd XOR rvg -> z01
+vdt OR tnw -> bfw
+bfw AND frj -> z10
+ffh OR nrd -> bqk
+y00 AND y03 -> djm
+y03 OR y00 -> psh
+bqk OR frj -> z08
+tnw OR fst -> frj
+gnj AND tgd -> z11
+bfw XOR mjb -> z00
+x03 OR x00 -> vdt
+gnj AND wpb -> z02
+x04 AND y00 -> kjc
+djm OR pbm -> qhw
+nrd AND vdt -> hwm
+kjc AND fst -> rvg
+y04 OR y02 -> fgs
+y01 AND x02 -> pbm
+ntg OR kjc -> kwq
+psh XOR fgs -> tgd
+qhw XOR tgd -> z09
+pbm OR djm -> kpj
+x03 XOR y03 -> ffh
+x00 XOR y04 -> ntg
+bfw OR bqk -> z06
+nrd XOR fgs -> wpb
+frj XOR qhw -> z04
+bqk OR frj -> z07
+y03 OR x01 -> nrd
+hwm AND bqk -> z03
+tgd XOR rvg -> z12
+tnw OR pbm -> gnj
+";
+
+example_tests! {
+    part_one_small: part_one(SMALL_EXAMPLE) => 4,
+    part_one: part_one(EXAMPLE) => 2024,
 }
 
 day_tests!(
     "day_24-1.dat",
     51107420031718,
-    2878072585763774253 /* cpm,ghp,gpr,krs,nks,z10,z21,z33 */
+    "cpm,ghp,gpr,krs,nks,z10,z21,z33"
 );
@@ -1,8 +1,12 @@
+use crate::answer::Answer;
 use crate::error::Result;
 use crate::input::Input;
-use crate::{day, day_tests};
+use crate::params::Params;
+use crate::solution::Solution;
+use crate::util::parse;
+use crate::{day_tests, example_tests};
 
-use log::info;
+use log::trace;
 
 type Columns = [u8; 5];
 
@@ -42,45 +46,49 @@ impl LockKeyInputParser {
         Self { input }
     }
 
-    fn parse_item(&mut self) -> Option<Item> {
-        let mut buffer: Vec<[u8; 5]> = Vec::new();
-
-        while let Some(line) = self.input.read_line() {
-            let line = line.trim_end();
-            if line.is_empty() {
-                break;
-            }
-            buffer.push(line.as_bytes().try_into().expect("5 bytes per input line"));
+    fn parse_item(&mut self) -> Result<Option<Item>> {
+        let Some(section) = parse::read_section(&mut self.input) else {
+            return Ok(None);
+        };
+
+        let buffer = section
+            .iter()
+            .map(|line| {
+                line.as_bytes()
+                    .try_into()
+                    .map_err(|_| line.line_error("a row of 5 # or . characters"))
+            })
+            .collect::<Result<Vec<[u8; 5]>, _>>()?;
+
+        if buffer.len() != 7 {
+            let last = &section[section.len() - 1];
+            Err(last.line_error("a lock or key of 7 rows"))?
         }
 
-        if buffer.is_empty() {
-            None
-        } else {
-            Some(Item::from_buffer(buffer))
-        }
+        Ok(Some(Item::from_buffer(buffer)))
     }
 }
 
-struct CodeChronicle {
+pub struct CodeChronicle {
     locks: Vec<Columns>,
     keys: Vec<Columns>,
 }
 
 impl CodeChronicle {
-    fn from_input(input: Input) -> Self {
+    fn from_input(input: Input) -> Result<Self> {
         let mut parser = LockKeyInputParser::new(input);
 
         let mut locks = Vec::new();
         let mut keys = Vec::new();
 
-        while let Some(item) = parser.parse_item() {
+        while let Some(item) = parser.parse_item()? {
             match item {
                 Item::Lock(columns) => locks.push(columns),
                 Item::Key(columns) => keys.push(columns),
             }
         }
 
-        Self { locks, keys }
+        Ok(Self { locks, keys })
     }
 
     fn matches(key: &Columns, lock: &Columns) -> bool {
@@ -92,7 +100,7 @@ impl CodeChronicle {
         for key in &self.keys {
             for lock in &self.locks {
                 if Self::matches(key, lock) {
-                    info!("Match: {:?} {:?}", key, lock);
+                    trace!(target: "day_25::trace", "Match: {:?} {:?}", key, lock);
                     matches += 1;
                 }
             }
@@ -101,15 +109,72 @@ impl CodeChronicle {
     }
 }
 
-pub fn run(input: Input, part: day::Part) -> Result<i64> {
-    let chronicle = CodeChronicle::from_input(input);
+pub struct Day;
+
+impl Solution for Day {
+    type Model = CodeChronicle;
+
+    const DAY: u8 = 25;
+    const TITLE: &'static str = "Code Chronicle";
 
-    let result = match part {
-        day::Part::One => chronicle.match_keys_and_locks(),
-        day::Part::Two => 0,
-    } as i64;
+    fn parse(input: Input, _: &Params) -> Result<Self::Model> {
+        CodeChronicle::from_input(input)
+    }
+
+    fn part_one(chronicle: &Self::Model) -> Result<Answer> {
+        Ok(chronicle.match_keys_and_locks().into())
+    }
+
+    fn part_two(_: &Self::Model) -> Result<Answer> {
+        Ok(0_i64.into())
+    }
+}
 
-    Ok(result)
+#[cfg(test)]
+const EXAMPLE: &str = "\
+#####
+.####
+.####
+.####
+.#.#.
+.#...
+.....
+
+#####
+##.##
+.#.##
+...##
+...#.
+...#.
+.....
+
+.....
+#....
+#....
+#...#
+#.#.#
+#.###
+#####
+
+.....
+.....
+#.#..
+###..
+###.#
+###.#
+#####
+
+.....
+.....
+.....
+#....
+#.#..
+#.#.#
+#####
+";
+
+example_tests! {
+    part_one: part_one(EXAMPLE) => 3,
 }
 
 day_tests!("day_25-1.dat", 3021, 0);
@@ -1,8 +1,9 @@
+use crate::answer::Answer;
 use crate::error::Result;
 use crate::input::Input;
-use crate::{day, day_tests};
-
-use log::info;
+use crate::params::Params;
+use crate::solution::Solution;
+use crate::{day_tests, example_tests};
 
 use std::iter::Peekable;
 
@@ -30,37 +31,27 @@ impl<'a> Scanner<'a> {
     }
 
     fn scan_next(&mut self) -> Option<Token> {
-        while let Some(c) = self.cursor.peek() {
-            match c {
-                '(' => {
-                    self.cursor.next();
-                    return Some(Token::LeftParen);
-                }
-                ')' => {
-                    self.cursor.next();
-                    return Some(Token::RightParen);
-                }
-                ',' => {
-                    self.cursor.next();
-                    return Some(Token::Comma);
-                }
-                'm' => {
-                    return self.read_mul();
-                }
-                'd' => {
-                    return self.read_do_dont();
-                }
-                c if c.is_numeric() => {
-                    return self.read_number();
-                }
-                _ => {
-                    self.consume_invalid_sequence();
-                    return Some(Token::Invalid);
-                }
+        match self.cursor.peek()? {
+            '(' => {
+                self.cursor.next();
+                Some(Token::LeftParen)
+            }
+            ')' => {
+                self.cursor.next();
+                Some(Token::RightParen)
+            }
+            ',' => {
+                self.cursor.next();
+                Some(Token::Comma)
+            }
+            'm' => self.read_mul(),
+            'd' => self.read_do_dont(),
+            c if c.is_numeric() => self.read_number(),
+            _ => {
+                self.consume_invalid_sequence();
+                Some(Token::Invalid)
             }
         }
-
-        return None;
     }
 
     fn valid_initial(c: char) -> bool {
@@ -142,18 +133,18 @@ impl Iterator for Scanner<'_> {
     type Item = Token;
 
     fn next(&mut self) -> Option<Self::Item> {
-        return self.scan_next();
+        self.scan_next()
     }
 }
 
-struct ComputerMemory {
+pub struct ComputerMemory {
     input: String,
 }
 
 impl ComputerMemory {
-    fn new(mut input: Input) -> Self {
-        let input = input.read_all();
-        Self { input }
+    fn new(mut input: Input) -> Result<Self> {
+        let input = input.read_all()?;
+        Ok(Self { input })
     }
 
     fn eval(&self, with_toggle: bool) -> i64 {
@@ -211,17 +202,40 @@ impl ComputerMemory {
     }
 }
 
-pub fn run(input: Input, part: day::Part) -> Result<i64> {
-    let memory = ComputerMemory::new(input);
+pub struct Day;
+
+impl Solution for Day {
+    type Model = ComputerMemory;
+
+    const DAY: u8 = 3;
+    const TITLE: &'static str = "Mull It Over";
+
+    fn parse(input: Input, _: &Params) -> Result<Self::Model> {
+        ComputerMemory::new(input)
+    }
+
+    fn part_one(memory: &Self::Model) -> Result<Answer> {
+        Ok(memory.eval(false).into())
+    }
+
+    fn part_two(memory: &Self::Model) -> Result<Answer> {
+        Ok(memory.eval(true).into())
+    }
+}
 
-    let result = match part {
-        day::Part::One => memory.eval(false),
-        day::Part::Two => memory.eval(true),
-    };
+#[cfg(test)]
+const EXAMPLE: &str = "\
+xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))
+";
 
-    info!("Day done ✅");
+#[cfg(test)]
+const EXAMPLE_WITH_CONDITIONALS: &str = "\
+xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))
+";
 
-    Ok(result)
+example_tests! {
+    part_one: part_one(EXAMPLE) => 161,
+    part_two: part_two(EXAMPLE_WITH_CONDITIONALS) => 48,
 }
 
 day_tests!("day_3-1.dat", 173731097, 93729253);
@@ -1,14 +1,16 @@
-use crate::error::{Error, Result};
+use crate::answer::Answer;
+use crate::error::{ParseError, Result};
 use crate::input::Input;
-use crate::{day, day_tests};
+use crate::params::Params;
+use crate::solution::Solution;
+use crate::{day_tests, example_tests};
 
 use std::borrow::Cow;
-use std::num::ParseIntError;
 
 type Rules = Vec<Vec<u8>>;
 type Pages = Vec<u8>;
 
-struct PrintIntstructions {
+pub struct PrintIntstructions {
     rules: Rules,
     updates: Vec<Pages>,
 }
@@ -24,20 +26,25 @@ impl PrintIntstructions {
                 break;
             }
 
-            let mut parts = line.split("|");
+            let line_no = input.line_number();
+            let (left, right) = line
+                .split_once("|")
+                .ok_or_else(|| ParseError::new(line_no, line, "a rule like 47|53"))?;
 
-            let left = parts.next().ok_or(Error::InvalidInput())?.parse::<u8>()?;
-            let right = parts.next().ok_or(Error::InvalidInput())?.parse::<u8>()?;
+            let left = Self::parse_page(line_no, line, left)?;
+            let right = Self::parse_page(line_no, line, right)?;
 
             rules[left as usize].push(right);
         }
 
-        for line in input.read_all().lines() {
+        while let Some(line) = input.read_line() {
+            let line = line.trim();
+            let line_no = input.line_number();
+
             let pages = line
-                .trim()
                 .split(",")
-                .map(|page| page.parse::<u8>())
-                .collect::<Result<Pages, ParseIntError>>()?;
+                .map(|page| Self::parse_page(line_no, line, page))
+                .collect::<Result<Pages>>()?;
 
             updates.push(pages);
         }
@@ -45,6 +52,13 @@ impl PrintIntstructions {
         Ok(PrintIntstructions { rules, updates })
     }
 
+    fn parse_page(line_no: usize, line: &str, page: &str) -> Result<u8> {
+        match page.parse::<u8>() {
+            Ok(page) if page < 100 => Ok(page),
+            _ => Err(ParseError::at(line_no, line, page, "a page number below 100").into()),
+        }
+    }
+
     fn validate_page_order<'a>(&self, pages: &'a Pages, do_fix: bool) -> (bool, Cow<'a, Pages>) {
         let mut pages = Cow::Borrowed(pages);
         let mut valid = true;
@@ -56,7 +70,7 @@ impl PrintIntstructions {
 
             // check pages up to i, if any is in the rule at the right side
             for j in 0..i {
-                let left_page = pages[j] as u8;
+                let left_page = pages[j];
                 if rules.contains(&left_page) {
                     valid = false;
 
@@ -99,15 +113,62 @@ impl PrintIntstructions {
     }
 }
 
-pub fn run(input: Input, part: day::Part) -> Result<i64> {
-    let instructions = PrintIntstructions::new(input)?;
+pub struct Day;
+
+impl Solution for Day {
+    type Model = PrintIntstructions;
 
-    let result = match part {
-        day::Part::One => instructions.valid_pages_metric(),
-        day::Part::Two => instructions.fixed_invalid_pages_metric(),
-    } as i64;
+    const DAY: u8 = 5;
+    const TITLE: &'static str = "Print Queue";
+
+    fn parse(input: Input, _: &Params) -> Result<Self::Model> {
+        PrintIntstructions::new(input)
+    }
+
+    fn part_one(instructions: &Self::Model) -> Result<Answer> {
+        Ok(instructions.valid_pages_metric().into())
+    }
+
+    fn part_two(instructions: &Self::Model) -> Result<Answer> {
+        Ok(instructions.fixed_invalid_pages_metric().into())
+    }
+}
 
-    Ok(result)
+#[cfg(test)]
+const EXAMPLE: &str = "\
+47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47
+";
+
+example_tests! {
+    part_one: part_one(EXAMPLE) => 143,
+    part_two: part_two(EXAMPLE) => 123,
 }
 
 day_tests!("day_5-1.dat", 5275, 6191);
@@ -1,100 +1,45 @@
+use crate::answer::Answer;
 use crate::error::Result;
 use crate::input::Input;
-use crate::{day, day_tests};
+use crate::params::Params;
+use crate::solution::Solution;
+use crate::util::grid::Grid;
+use crate::util::parallel;
+use crate::util::{Dir4, Vec2};
+use crate::{day_tests, example_tests};
 
 use std::collections::HashSet;
 use std::fmt::{self, Display};
 
-type Map = Vec<Vec<u8>>;
-
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl Direction {
-    fn turn(self) -> Direction {
-        match self {
-            Direction::Up => Direction::Right,
-            Direction::Right => Direction::Down,
-            Direction::Down => Direction::Left,
-            Direction::Left => Direction::Up,
-        }
-    }
-}
-
-#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, PartialOrd)]
-struct Pos {
-    x: i32,
-    y: i32,
-}
-
-impl Pos {
-    fn new(x: usize, y: usize) -> Pos {
-        Pos {
-            x: x as i32,
-            y: y as i32,
-        }
-    }
-
-    fn peek(&self, dir: Direction) -> Pos {
-        let update_vec = match dir {
-            Direction::Up => (0, -1),
-            Direction::Down => (0, 1),
-            Direction::Left => (-1, 0),
-            Direction::Right => (1, 0),
-        };
-        return *self + update_vec;
-    }
-}
-
-impl std::ops::Add<(i32, i32)> for Pos {
-    type Output = Pos;
-
-    fn add(self, rhs: (i32, i32)) -> Pos {
-        Pos {
-            x: self.x + rhs.0,
-            y: self.y + rhs.1,
-        }
-    }
-}
+type Map = Grid<u8>;
 
 // TODO: rename to guard vector or something
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 struct GuardVec {
-    pos: Pos,
-    direction: Direction,
+    pos: Vec2,
+    direction: Dir4,
 }
 
 impl GuardVec {
-    fn new(pos: Pos) -> GuardVec {
+    fn new(pos: Vec2) -> GuardVec {
         GuardVec {
             pos,
-            direction: Direction::Up,
+            direction: Dir4::Up,
         }
     }
 
     fn turn(&mut self) {
-        self.direction = self.direction.turn();
+        self.direction = self.direction.cw();
     }
 
     fn walk(&mut self) {
-        self.pos = self.pos.peek(self.direction);
+        self.pos += self.direction.delta();
     }
 }
 
 impl Display for GuardVec {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let dir_symbol = match self.direction {
-            Direction::Up => '^',
-            Direction::Down => 'v',
-            Direction::Left => '<',
-            Direction::Right => '>',
-        };
-        write!(f, "{}", dir_symbol)
+        write!(f, "{}", self.direction)
     }
 }
 
@@ -108,118 +53,66 @@ enum MapElement {
 #[allow(dead_code)]
 struct LabWalker<'a> {
     lab: &'a LabMap,
-    extra_wall: (i32, i32),
+    extra_wall: Vec2,
 }
 
 #[allow(dead_code)]
 impl<'a> LabWalker<'a> {
-    fn new(lab: &'a LabMap, extra_wall: (i32, i32)) -> Self {
+    fn new(lab: &'a LabMap, extra_wall: Vec2) -> Self {
         LabWalker { lab, extra_wall }
     }
 
-    fn at(&self, x: i32, y: i32) -> u8 {
-        self.lab.map[y as usize][x as usize]
-    }
-
     fn walk_or_die(&self) -> bool {
-        let width = self.lab.width();
-        let height = self.lab.height();
+        let max_steps = self.lab.map.width() * self.lab.map.height();
 
-        let (mut x, mut y) = (self.lab.guard.pos.x, self.lab.guard.pos.y);
-        let mut dir = 0;
-        // 0 - up, 1 - right, 2 - down, 3 - left
+        let mut pos = self.lab.guard.pos;
+        let mut dir = Dir4::Up;
 
         let mut steps = 0;
         loop {
-            if (dir == 0 && y == 0)
-                || (dir == 1 && x == width - 1)
-                || (dir == 2 && y == height - 1)
-                || (dir == 3 && x == 0)
-            {
-                break;
-            }
-
-            let (xx, yy) = match dir {
-                0 => (x, y - 1),
-                1 => (x + 1, y),
-                2 => (x, y + 1),
-                3 => (x - 1, y),
-                _ => unreachable!(),
-            };
-
-            if self.at(xx, yy) == b'#' || (xx, yy) == self.extra_wall {
-                // wall
-                dir = (dir + 1) % 4;
-            } else {
-                x = xx;
-                y = yy;
-                steps += 1;
+            let next = pos + dir.delta();
+
+            match self.lab.map.get(next) {
+                None => break,
+                Some(b'#') => dir = dir.cw(),
+                Some(_) if next == self.extra_wall => dir = dir.cw(),
+                Some(_) => {
+                    pos = next;
+                    steps += 1;
+                }
             }
 
-            if steps > width * height {
+            if steps > max_steps {
                 return true;
             }
         }
 
-        return false;
+        false
     }
 }
 
-struct LabMap {
+pub struct LabMap {
     map: Map,
     guard: GuardVec,
 }
 
 impl LabMap {
-    // TODO: nice input reader iterator
     fn new(mut input: Input) -> Result<LabMap> {
-        let mut map = Vec::new();
-        let mut y = 0;
-        let mut guard: Option<GuardVec> = None;
-
-        while let Some(mut map_line) = input.read_line_as_bytes() {
-            map_line.pop();
-
-            if guard.is_none() {
-                for x in 0..map_line.len() {
-                    if map_line[x] == b'^' {
-                        guard = Some(GuardVec::new(Pos::new(x, y)));
-                    }
-                }
-            }
+        let (map, markers) = Grid::parse(&mut input, &['^'], |c| match c {
+            '.' | '#' | '^' => Ok(c as u8),
+            _ => Err("a map tile ., # or ^"),
+        })?;
 
-            map.push(map_line);
-            y += 1;
-        }
+        let guard = GuardVec::new(markers.single('^')?);
 
-        Ok(LabMap {
-            map,
-            guard: guard.expect("Guard should be in the map"),
-        })
+        Ok(LabMap { map, guard })
     }
 
-    fn width(&self) -> i32 {
-        self.map[0].len() as i32
-    }
-
-    fn height(&self) -> i32 {
-        self.map.len() as i32
-    }
-
-    fn at(&self, pos: Pos) -> MapElement {
-        match (pos.x, pos.y) {
-            (x, y)
-                if x < 0
-                    || y < 0
-                    || y >= self.map.len() as i32
-                    || x >= self.map[y as usize].len() as i32 =>
-            {
-                MapElement::OutOfBounds
-            }
-            (x, y) => match self.map[y as usize][x as usize] {
-                b'#' => MapElement::Wall,
-                _ => MapElement::Empty,
-            },
+    fn at(&self, pos: Vec2) -> MapElement {
+        match self.map.get(pos) {
+            None => MapElement::OutOfBounds,
+            Some(b'#') => MapElement::Wall,
+            Some(_) => MapElement::Empty,
         }
     }
 
@@ -240,45 +133,45 @@ impl LabMap {
             GuardWalkIterator::new(self).for_each(|g| path.push(g));
         }
 
-        let mut total_possible_wall_placements = 0;
+        // a wall is placed where the guard first steps on the cell, so the walk with it can
+        // start right before that step
         let mut already_tested = HashSet::new();
-
-        // for guard in &path {
-        for i in 0..path.len() - 1 {
-            let extra_wall = path[i + 1].pos;
-            if self.at(extra_wall) == MapElement::Wall || extra_wall == self.guard.pos {
-                continue;
-            }
-
-            if !already_tested.insert(extra_wall) {
-                continue;
-            }
-
-            let mut walk_with_extra_all = GuardWalkIterator::new(&self)
-                .with_extra_wall(extra_wall)
-                .with_start(path[i].clone());
+        let candidates = path
+            .windows(2)
+            .filter(|step| {
+                let extra_wall = step[1].pos;
+                self.at(extra_wall) != MapElement::Wall
+                    && extra_wall != self.guard.pos
+                    && already_tested.insert(extra_wall)
+            })
+            .map(|step| (step[0].clone(), step[1].pos))
+            .collect::<Vec<_>>();
+
+        parallel::map(&candidates, |(start, extra_wall)| {
+            let mut walk_with_extra_all = GuardWalkIterator::new(self)
+                .with_extra_wall(*extra_wall)
+                .with_start(start.clone());
 
             for _ in &mut walk_with_extra_all {
                 // just walk
             }
 
-            if walk_with_extra_all.has_cycle() {
-                total_possible_wall_placements += 1;
-            }
-        }
-
-        total_possible_wall_placements
+            walk_with_extra_all.has_cycle()
+        })
+        .into_iter()
+        .filter(|&has_cycle| has_cycle)
+        .count()
     }
 
     #[allow(dead_code)]
     fn count_walls_to_cycle_guard_simple(&self) -> usize {
         let mut total_possible_wall_placements = 0;
 
-        for j in 0..self.map.len() {
-            for i in 0..self.map[j].len() {
-                let extra_wall = (i as i32, j as i32);
+        for j in 0..self.map.height() {
+            for i in 0..self.map.width() {
+                let extra_wall = Vec2::cell(i, j);
 
-                let has_cycle = LabWalker::new(&self, extra_wall).walk_or_die();
+                let has_cycle = LabWalker::new(self, extra_wall).walk_or_die();
                 if has_cycle {
                     total_possible_wall_placements += 1;
                 }
@@ -294,7 +187,7 @@ struct GuardWalkIterator<'a> {
     guard: GuardVec,
     visited: HashSet<GuardVec>,
 
-    extra_wall: Option<Pos>,
+    extra_wall: Option<Vec2>,
     cycle: bool,
 }
 
@@ -309,7 +202,7 @@ impl<'a> GuardWalkIterator<'a> {
         }
     }
 
-    fn with_extra_wall(mut self, pos: Pos) -> Self {
+    fn with_extra_wall(mut self, pos: Vec2) -> Self {
         self.extra_wall = Some(pos);
         self
     }
@@ -323,7 +216,7 @@ impl<'a> GuardWalkIterator<'a> {
         self.cycle
     }
 
-    fn at(&self, pos: Pos) -> MapElement {
+    fn at(&self, pos: Vec2) -> MapElement {
         match self.extra_wall {
             Some(extra_wall) if extra_wall == pos => MapElement::Wall,
             _ => self.map.at(pos),
@@ -335,7 +228,7 @@ impl<'a> Iterator for GuardWalkIterator<'a> {
     type Item = GuardVec;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let next_pos = self.guard.pos.peek(self.guard.direction);
+        let next_pos = self.guard.pos + self.guard.direction.delta();
 
         let next = self.at(next_pos);
         match next {
@@ -361,18 +254,59 @@ impl<'a> Iterator for GuardWalkIterator<'a> {
     }
 }
 
-pub fn run(input: Input, part: day::Part) -> Result<i64> {
-    let lab_map = LabMap::new(input)?;
+pub struct Day;
 
-    let result = match part {
-        day::Part::One => lab_map.guard_walk(),
-        day::Part::Two => {
-            // lab_map.count_walls_to_cycle_guard_simple();
-            lab_map.find_walls_to_cycle_guard()
-        }
-    } as i64;
+impl Solution for Day {
+    type Model = LabMap;
+
+    const DAY: u8 = 6;
+    const TITLE: &'static str = "Guard Gallivant";
+
+    fn parse(input: Input, _: &Params) -> Result<Self::Model> {
+        LabMap::new(input)
+    }
+
+    fn part_one(lab_map: &Self::Model) -> Result<Answer> {
+        Ok(lab_map.guard_walk().into())
+    }
 
-    Ok(result)
+    fn part_two(lab_map: &Self::Model) -> Result<Answer> {
+        // lab_map.count_walls_to_cycle_guard_simple();
+        Ok(lab_map.find_walls_to_cycle_guard().into())
+    }
+}
+
+#[cfg(test)]
+const EXAMPLE: &str = "\
+....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...
+";
+
+example_tests! {
+    part_one: part_one(EXAMPLE) => 41,
+    part_two: part_two(EXAMPLE) => 6,
+}
+
+#[cfg(test)]
+mod test_parallel {
+    use super::*;
+    use crate::util::parallel::test::with_threads;
+
+    #[test]
+    fn same_loops_on_any_thread_count() {
+        let lab = Day::parse(Input::from_str(EXAMPLE), &Params::new()).unwrap();
+        let serial = with_threads(1, || Day::part_two(&lab).unwrap());
+
+        assert_eq!(with_threads(4, || Day::part_two(&lab).unwrap()), serial);
+    }
 }
 
 day_tests!("day_6-1.dat", 5531, 2165);
@@ -1,6 +1,12 @@
-use crate::error::Result;
+use crate::answer::Answer;
+use crate::error::{ParseError, Result};
 use crate::input::Input;
-use crate::{day, day_tests};
+use crate::params::Params;
+use crate::solution::Solution;
+use crate::util::parallel;
+use crate::{day_tests, example_tests};
+
+use log::trace;
 
 /**
  * Generates sequences of numbers from 0 to m-1 of length n
@@ -26,8 +32,6 @@ impl SeqGenerator {
     fn next(&mut self) -> Option<&[u8]> {
         let mut done = false;
         for i in 0..self.n {
-            let i = i as usize;
-
             let a = self.buf[i].wrapping_add(1);
             if a < self.m {
                 // value at i can be incremented
@@ -76,7 +80,7 @@ struct BridgeEquation {
 
 impl BridgeEquation {
     fn try_eval(&self, ops: &[u8]) -> Option<i64> {
-        let mut result = self.operands[0] as i64;
+        let mut result = self.operands[0];
 
         for (i, arg) in self.operands.iter().skip(1).enumerate() {
             let arg = *arg;
@@ -86,7 +90,7 @@ impl BridgeEquation {
                 OP_ADD => result + arg,
                 OP_CONCAT => {
                     let shift = (arg as f64).log10().floor() as u32 + 1;
-                    10_i64.pow(shift as u32) * result + arg
+                    10_i64.pow(shift) * result + arg
                 }
                 _ => unreachable!(),
             };
@@ -103,25 +107,25 @@ impl BridgeEquation {
         None
     }
 
-    fn print_solution(&self, operators: &[u8]) {
-        print!("{}", self.operands[0]);
+    fn format_solution(&self, operators: &[u8]) -> String {
+        let mut out = self.operands[0].to_string();
 
         for (i, arg) in self.operands.iter().skip(1).enumerate() {
             match operators[i] {
-                OP_MUL => print!(" * {}", arg),
-                OP_ADD => print!(" + {}", arg),
-                OP_CONCAT => print!("||{}", arg),
+                OP_MUL => out += &format!(" * {}", arg),
+                OP_ADD => out += &format!(" + {}", arg),
+                OP_CONCAT => out += &format!("||{}", arg),
                 _ => unreachable!(),
             }
         }
 
-        println!(" = {}", self.result);
+        format!("{out} = {}", self.result)
     }
 
     fn has_solution(&self, mut gen: SeqGenerator) -> bool {
         while let Some(ops) = gen.next() {
-            if let Some(_) = self.try_eval(ops) {
-                self.print_solution(ops);
+            if self.try_eval(ops).is_some() {
+                trace!(target: "day_7::trace", "{}", self.format_solution(ops));
                 return true;
             }
         }
@@ -140,42 +144,44 @@ impl BridgeEquation {
     }
 }
 
-struct RopeBridgeCalculations {
+pub struct RopeBridgeCalculations {
     equations: Vec<BridgeEquation>,
 }
 
 impl RopeBridgeCalculations {
-    fn new(input: Input) -> Self {
+    fn new(input: Input) -> Result<Self> {
         let equations = input
             .lines()
-            .map(|line| {
-                let line = line.unwrap();
-                let mut parts = line.split(": ");
+            .enumerate()
+            .map(|(i, line)| {
+                let line = line?;
+                let line_no = i + 1;
+
+                let (result, operands) = line.split_once(": ").ok_or_else(|| {
+                    ParseError::new(line_no, &line, "an equation like 190: 10 19")
+                })?;
 
-                let result = parts.next().unwrap().parse::<i64>().unwrap();
+                let number = |s: &str| {
+                    s.parse::<i64>()
+                        .map_err(|_| ParseError::at(line_no, &line, s, "a number"))
+                };
 
-                let operands = parts
-                    .next()
-                    .unwrap()
-                    .split(" ")
-                    .map(|op| op.parse::<i64>().unwrap())
-                    .collect();
+                let result = number(result)?;
+                let operands = operands.split(" ").map(number).collect::<Result<_, _>>()?;
 
-                BridgeEquation { result, operands }
+                Ok(BridgeEquation { result, operands })
             })
-            .collect();
+            .collect::<Result<_>>()?;
 
-        Self { equations }
+        Ok(Self { equations })
     }
 
     fn find_solvable_eqs_sum<F>(&self, solver: F) -> i64
     where
-        F: Fn(&BridgeEquation) -> bool,
+        F: Fn(&BridgeEquation) -> bool + Sync,
     {
-        self.equations
-            .iter()
-            .filter(|eq| solver(eq))
-            .map(|eq| eq.result)
+        parallel::map(&self.equations, |eq| if solver(eq) { eq.result } else { 0 })
+            .into_iter()
             .sum()
     }
 
@@ -188,15 +194,43 @@ impl RopeBridgeCalculations {
     }
 }
 
-pub fn run(input: Input, part: day::Part) -> Result<i64> {
-    let bridge_calcs = RopeBridgeCalculations::new(input);
+pub struct Day;
 
-    let result = match part {
-        day::Part::One => bridge_calcs.find_simple_solvable_eqs_sum(),
-        day::Part::Two => bridge_calcs.find_solvable_with_concat_eqs_sum(),
-    };
+impl Solution for Day {
+    type Model = RopeBridgeCalculations;
+
+    const DAY: u8 = 7;
+    const TITLE: &'static str = "Bridge Repair";
+
+    fn parse(input: Input, _: &Params) -> Result<Self::Model> {
+        RopeBridgeCalculations::new(input)
+    }
+
+    fn part_one(bridge_calcs: &Self::Model) -> Result<Answer> {
+        Ok(bridge_calcs.find_simple_solvable_eqs_sum().into())
+    }
+
+    fn part_two(bridge_calcs: &Self::Model) -> Result<Answer> {
+        Ok(bridge_calcs.find_solvable_with_concat_eqs_sum().into())
+    }
+}
 
-    Ok(result)
+#[cfg(test)]
+const EXAMPLE: &str = "\
+190: 10 19
+3267: 81 40 27
+83: 17 5
+156: 15 6
+7290: 6 8 6 15
+161011: 16 10 13
+192: 17 8 14
+21037: 9 7 18 13
+292: 11 6 16 20
+";
+
+example_tests! {
+    part_one: part_one(EXAMPLE) => 3749,
+    part_two: part_two(EXAMPLE) => 11387,
 }
 
 day_tests!("day_7-1.dat", 1298300076754, 248427118972289);
@@ -1,6 +1,11 @@
+use crate::answer::Answer;
 use crate::error::Result;
 use crate::input::Input;
-use crate::{day, day_tests};
+use crate::params::Params;
+use crate::solution::Solution;
+use crate::util::grid::Grid;
+use crate::util::Vec2;
+use crate::{day_tests, example_tests};
 
 use std::collections::HashSet;
 
@@ -15,7 +20,10 @@ impl SubsetGenerator {
     pub fn new(m: u8, n: usize) -> Self {
         let mut indices = (0..m as usize).collect::<Vec<usize>>();
 
-        indices.last_mut().map(|x| *x -= 1); // this is a trick so that first call to next yields the initial configuration
+        // this is a trick so that first call to next_subset yields the initial configuration
+        if let Some(x) = indices.last_mut() {
+            *x -= 1;
+        }
 
         Self {
             m: m as usize,
@@ -24,7 +32,7 @@ impl SubsetGenerator {
         }
     }
 
-    pub fn next(&mut self) -> Option<&[usize]> {
+    pub fn next_subset(&mut self) -> Option<&[usize]> {
         // indices ...[a, b, c, ...] pointers to the original set,
 
         // 1) find index such that it can be incremented
@@ -42,10 +50,8 @@ impl SubsetGenerator {
                 self.indices[k] = a;
 
                 // reset all above k
-                let mut reset_val = a + 1;
-                for j in k + 1..self.m as usize {
+                for (j, reset_val) in (k + 1..self.m).zip(a + 1..) {
                     self.indices[j] = reset_val;
-                    reset_val += 1;
                 }
 
                 done = false;
@@ -61,138 +67,53 @@ impl SubsetGenerator {
     }
 }
 
-#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
-struct Vec2 {
-    x: i32,
-    y: i32,
-}
-
-impl Vec2 {
-    fn make_vector(a: &Vec2, b: &Vec2) -> Self {
-        Self {
-            x: b.x - a.x,
-            y: b.y - a.y,
-        }
-    }
-
-    fn inv(&self) -> Self {
-        Self {
-            x: -self.x,
-            y: -self.y,
-        }
-    }
-
-    fn in_bounds(&self, width: i32, height: i32) -> bool {
-        self.x >= 0 && self.x < width && self.y >= 0 && self.y < height
-    }
-}
-
-impl std::ops::Add for Vec2 {
-    type Output = Self;
-
-    fn add(self, rhs: Self) -> Self {
-        Self {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-        }
-    }
-}
-
-impl From<(usize, usize)> for Vec2 {
-    fn from((x, y): (usize, usize)) -> Self {
-        Self {
-            x: x as i32,
-            y: y as i32,
-        }
-    }
-}
-
-struct AntinodeIterator {
-    pos: Vec2,
-    dir: Vec2,
-    width: i32,
-    height: i32,
-}
-
-impl AntinodeIterator {
-    fn new(start: Vec2, dir: Vec2, map: &CityAntennaMap) -> Self {
-        Self {
-            pos: start,
-            dir,
-            width: map.width,
-            height: map.height,
-        }
-    }
-}
-
-impl Iterator for AntinodeIterator {
-    type Item = Vec2;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let next = self.pos;
-
-        if !next.in_bounds(self.width, self.height) {
-            return None;
-        }
-
-        self.pos = self.pos + self.dir;
-        Some(next)
-    }
-}
-
 type Antennas = Vec<Vec2>;
 
-struct CityAntennaMap {
+pub struct CityAntennaMap {
+    map: Grid<u8>,
     antennas_at_freq: Vec<Antennas>,
-    width: i32,
-    height: i32,
 }
 
 impl CityAntennaMap {
-    fn new(input: Input) -> Self {
-        let mut antennas_at_freq = vec![Antennas::new(); 128];
-
-        let mut width = 0_usize;
-        let mut height = 0_usize;
+    fn new(mut input: Input) -> Result<Self> {
+        let (map, _) = Grid::parse(&mut input, &[], |c| {
+            c.is_ascii()
+                .then_some(c as u8)
+                .ok_or("an ASCII antenna frequency")
+        })?;
 
-        input.lines().enumerate().for_each(|(y, line)| {
-            let line = line.expect("valid input");
-
-            height = y + 1;
-            width = line.len();
-
-            line.char_indices().for_each(|(x, c)| match c {
-                '.' => {}
-                freq => {
-                    antennas_at_freq
-                        .get_mut(freq as usize)
-                        .expect("input antennas are lower ASCII symbols")
-                        .push(Vec2::from((x, y)));
-                }
-            });
-        });
+        let mut antennas_at_freq = vec![Antennas::new(); 128];
+        for (pos, &freq) in map.iter() {
+            if freq != b'.' {
+                antennas_at_freq[freq as usize].push(pos);
+            }
+        }
 
-        Self {
+        Ok(Self {
+            map,
             antennas_at_freq,
-            width: width as i32,
-            height: height as i32,
-        }
+        })
+    }
+
+    // positions from `start` on in the direction of `dir`, as long as they're on the map
+    fn antinodes_from(&self, start: Vec2, dir: Vec2) -> impl Iterator<Item = Vec2> + '_ {
+        self.map.ray(start, dir).map(|(pos, _)| pos)
     }
 
-    fn adjacent_antinodes(&self, a: &Vec2, b: &Vec2) -> impl Iterator<Item = Vec2> {
-        let ab = Vec2::make_vector(a, b);
+    fn adjacent_antinodes(&self, a: &Vec2, b: &Vec2) -> impl Iterator<Item = Vec2> + '_ {
+        let ab = *b - a;
 
-        let a_antinode = AntinodeIterator::new(*a, ab.inv(), self).skip(1).take(1);
-        let b_antinode = AntinodeIterator::new(*b, ab, self).skip(1).take(1);
+        let a_antinode = self.antinodes_from(*a, -ab).skip(1).take(1);
+        let b_antinode = self.antinodes_from(*b, ab).skip(1).take(1);
 
         a_antinode.chain(b_antinode)
     }
 
-    fn all_antinodes(&self, a: &Vec2, b: &Vec2) -> impl Iterator<Item = Vec2> {
-        let ab = Vec2::make_vector(a, b);
+    fn all_antinodes(&self, a: &Vec2, b: &Vec2) -> impl Iterator<Item = Vec2> + '_ {
+        let ab = *b - a;
 
-        let a_antinodes = AntinodeIterator::new(*a, ab.inv(), self);
-        let b_antinodes = AntinodeIterator::new(*b, ab, self);
+        let a_antinodes = self.antinodes_from(*a, -ab);
+        let b_antinodes = self.antinodes_from(*b, ab);
 
         a_antinodes.chain(b_antinodes)
     }
@@ -206,7 +127,7 @@ impl CityAntennaMap {
 
         let mut pairs_gen = SubsetGenerator::new(2, antennas.len());
 
-        while let Some(pair) = pairs_gen.next() {
+        while let Some(pair) = pairs_gen.next_subset() {
             match pair {
                 [a, b] => {
                     if all {
@@ -225,8 +146,7 @@ impl CityAntennaMap {
     fn find_antinodes(&self, all: bool) -> usize {
         self.antennas_at_freq
             .iter()
-            .map(|antennas| self.find_antinodes_for_freq(antennas, all))
-            .flatten()
+            .flat_map(|antennas| self.find_antinodes_for_freq(antennas, all))
             .collect::<HashSet<Vec2>>()
             .len()
     }
@@ -240,16 +160,46 @@ impl CityAntennaMap {
     }
 }
 
-#[allow(unreachable_code, unused_variables, unused_mut)]
-pub fn run(input: Input, part: day::Part) -> Result<i64> {
-    let city_antenna_map = CityAntennaMap::new(input);
+pub struct Day;
+
+impl Solution for Day {
+    type Model = CityAntennaMap;
 
-    let result = match part {
-        day::Part::One => city_antenna_map.find_adjecent_antinodes(),
-        day::Part::Two => city_antenna_map.find_all_antinodes(),
-    } as i64;
+    const DAY: u8 = 8;
+    const TITLE: &'static str = "Resonant Collinearity";
+
+    fn parse(input: Input, _: &Params) -> Result<Self::Model> {
+        CityAntennaMap::new(input)
+    }
+
+    fn part_one(city_antenna_map: &Self::Model) -> Result<Answer> {
+        Ok(city_antenna_map.find_adjecent_antinodes().into())
+    }
+
+    fn part_two(city_antenna_map: &Self::Model) -> Result<Answer> {
+        Ok(city_antenna_map.find_all_antinodes().into())
+    }
+}
 
-    Ok(result)
+#[cfg(test)]
+const EXAMPLE: &str = "\
+............
+........0...
+.....0......
+.......0....
+....0.......
+......A.....
+............
+............
+........A...
+.........A..
+............
+............
+";
+
+example_tests! {
+    part_one: part_one(EXAMPLE) => 14,
+    part_two: part_two(EXAMPLE) => 34,
 }
 
 day_tests!("day_8-1.dat", 249, 905);
@@ -1,8 +1,11 @@
 use std::collections::HashSet;
 
-use crate::error::Result;
+use crate::answer::Answer;
+use crate::error::{ParseError, Result};
 use crate::input::Input;
-use crate::{day, day_tests};
+use crate::params::Params;
+use crate::solution::Solution;
+use crate::{day_tests, example_tests};
 
 type FileId = usize;
 type Length = u8;
@@ -19,11 +22,8 @@ impl Segment {
         match self {
             Segment::File { length, id } => {
                 let length = *length as usize;
-                let id = *id as usize;
-                let checksum = (0..length)
-                    .into_iter()
-                    .map(|i| (base + i) * id)
-                    .sum::<usize>();
+                let id = *id;
+                let checksum = (0..length).map(|i| (base + i) * id).sum::<usize>();
                 (checksum, length, Some(id))
             }
             Segment::Gap { length } => (0, *length as usize, None),
@@ -34,7 +34,7 @@ impl Segment {
 type Segments = Vec<Segment>;
 
 // that's a cool name btw, no wonder amphipods are (c)rustaceans ;)
-struct AmphipodFileSystem {
+pub struct AmphipodFileSystem {
     raw_disk_map: RawDiskMap,
     segments: Segments,
 }
@@ -47,7 +47,7 @@ struct SegmentsBackCursor<'a> {
 
 impl<'a> SegmentsBackCursor<'a> {
     fn new(disk_map: &'a RawDiskMap) -> Self {
-        let disk_map_index = if disk_map[disk_map.len() - 1] % 2 == 0 {
+        let disk_map_index = if disk_map[disk_map.len() - 1].is_multiple_of(2) {
             disk_map.len() - 1
         } else {
             disk_map.len() - 2
@@ -65,7 +65,7 @@ impl<'a> SegmentsBackCursor<'a> {
         let file_id = self.disk_map_index / 2;
 
         for i in 0..self.remaining {
-            checksum += (base_index + i) * file_id as usize;
+            checksum += (base_index + i) * file_id;
         }
 
         checksum
@@ -76,7 +76,7 @@ impl<'a> SegmentsBackCursor<'a> {
             return None;
         }
 
-        if self.remaining <= 0 {
+        if self.remaining == 0 {
             loop {
                 let next_index = self.disk_map_index as i64 - 2;
 
@@ -102,15 +102,24 @@ impl<'a> SegmentsBackCursor<'a> {
 }
 
 impl AmphipodFileSystem {
-    fn new(mut input: Input) -> Self {
-        let mut buffer = vec![];
-        input.read_line_as_bytes_into(&mut buffer);
-        buffer.pop(); // remove the newline
-
-        let disk_map = buffer
-            .into_iter()
-            .map(|x| x - '0' as u8)
-            .collect::<RawDiskMap>();
+    fn new(mut input: Input) -> Result<Self> {
+        let line = input
+            .read_line()
+            .ok_or_else(|| ParseError::eof(1, "a disk map"))?;
+        let line = line.trim_end();
+
+        let disk_map = line
+            .char_indices()
+            .map(|(i, c)| match c.to_digit(10) {
+                Some(x) => Ok(x as u8),
+                None => Err(ParseError::at(
+                    1,
+                    line,
+                    &line[i..i + c.len_utf8()],
+                    "a digit",
+                )),
+            })
+            .collect::<Result<RawDiskMap, _>>()?;
 
         let segments = disk_map
             .iter()
@@ -127,10 +136,10 @@ impl AmphipodFileSystem {
                 acc
             });
 
-        Self {
+        Ok(Self {
             raw_disk_map: disk_map,
             segments,
-        }
+        })
     }
 
     fn rellocate_segment(
@@ -158,16 +167,14 @@ impl AmphipodFileSystem {
                     }
                     return Some(i);
                 }
-                Segment::File { id, .. } => {
-                    if *id == file_id {
-                        return None;
-                    }
+                Segment::File { id, .. } if *id == file_id => {
+                    return None;
                 }
                 _ => {}
             }
         }
 
-        return None;
+        None
     }
 
     fn segments_checksum(segments: &Segments) -> usize {
@@ -176,8 +183,8 @@ impl AmphipodFileSystem {
         let mut index = 0;
         let mut checksum = 0;
 
-        for i in 0..segments.len() {
-            let (partial_checksum, advance, file_id) = segments[i].checksum(index);
+        for segment in segments {
+            let (partial_checksum, advance, file_id) = segment.checksum(index);
             if let Some(file_id) = file_id {
                 let new_file = visited_file_ids.insert(file_id);
                 if new_file {
@@ -209,7 +216,7 @@ impl AmphipodFileSystem {
                     let file_length = self.raw_disk_map[disk_map_index] as usize;
 
                     for j in 0..file_length {
-                        checksum += (checksum_index + j) * file_id as usize;
+                        checksum += (checksum_index + j) * file_id;
                     }
 
                     checksum_index += file_length;
@@ -224,7 +231,7 @@ impl AmphipodFileSystem {
                             "segments back cursor is always larger the current disk map index",
                         ) / 2;
 
-                        checksum += (checksum_index + j) * file_id as usize;
+                        checksum += (checksum_index + j) * file_id;
                     }
                     checksum_index += gap_length;
                 }
@@ -250,15 +257,35 @@ impl AmphipodFileSystem {
     }
 }
 
-pub fn run(input: Input, part: day::Part) -> Result<i64> {
-    let afs = AmphipodFileSystem::new(input);
+pub struct Day;
+
+impl Solution for Day {
+    type Model = AmphipodFileSystem;
+
+    const DAY: u8 = 9;
+    const TITLE: &'static str = "Disk Fragmenter";
+
+    fn parse(input: Input, _: &Params) -> Result<Self::Model> {
+        AmphipodFileSystem::new(input)
+    }
+
+    fn part_one(afs: &Self::Model) -> Result<Answer> {
+        Ok(afs.fragmented_checksum().into())
+    }
+
+    fn part_two(afs: &Self::Model) -> Result<Answer> {
+        Ok(afs.defragmented_checksum().into())
+    }
+}
 
-    let result = match part {
-        day::Part::One => afs.fragmented_checksum(),
-        day::Part::Two => afs.defragmented_checksum(),
-    } as i64;
+#[cfg(test)]
+const EXAMPLE: &str = "\
+2333133121414131402
+";
 
-    Ok(result)
+example_tests! {
+    part_one: part_one(EXAMPLE) => 1928,
+    part_two: part_two(EXAMPLE) => 2858,
 }
 
 day_tests!("day_9-1.dat", 6386640365805, 6423258376982);
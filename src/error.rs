@@ -1,6 +1,6 @@
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
-    #[error("Argument error: {0}")]
+    #[error("{0}")]
     Argument(#[from] ArgumentError),
 
     #[error("Solution for day {0} not implemented yet")]
@@ -9,12 +9,45 @@ pub enum Error {
     #[error("Input file not found: {0}")]
     InputFileNotFound(String),
 
-    #[error("Invalid input")]
-    InvalidInput(),
+    #[error("Stdin is an interactive terminal, pipe or redirect the input instead")]
+    StdinIsTerminal,
+
+    #[error("{0}")]
+    Parse(#[from] ParseError),
+
+    #[error("Invalid value '{1}' for parameter {0}")]
+    InvalidParam(&'static str, String),
+
+    #[error("Invalid line {1} in answers file {0}, expected <day> <part> <input> <answer>")]
+    InvalidAnswersFile(String, usize),
+
+    #[error("Answers file not found: {0}, write one with --record")]
+    AnswersFileNotFound(String),
+
+    #[error("{0} answer(s) differ from the answers file")]
+    WrongAnswers(usize),
+
+    #[error("{0} answer(s) missing from the answers file")]
+    MissingAnswers(usize),
 
     #[error("No solution: {0}")]
     NoSolution(String),
 
+    #[error("No solution: {0} ran out of its budget of {1}")]
+    OutOfBudget(&'static str, String),
+
+    #[error("Day {0} already exists: {1}")]
+    DayExists(u8, String),
+
+    #[error("Cannot create the day: {0}")]
+    Scaffold(String),
+
+    #[error("Panicked at {1}: {0}")]
+    Panic(String, String),
+
+    #[error("{0} part(s) failed")]
+    FailedParts(usize),
+
     // derived errors
     #[error("I/O error: {0}")]
     StdIo(#[from] std::io::Error),
@@ -25,20 +58,227 @@ pub enum Error {
 
 #[derive(Debug, thiserror::Error)]
 pub enum ArgumentError {
-    #[error("Missing argument")]
+    #[error("Missing argument: {0}")]
     MissingArgument(&'static str),
 
-    #[error("Invalid day input: {0}")]
+    #[error("Missing value for {0}")]
+    MissingValue(&'static str),
+
+    #[error("Invalid day '{0}', expected a day, a range like 5..=12 or all")]
     InvalidDayInput(String),
 
-    #[error("Invalid day: {0}")]
+    #[error("Invalid day {0}, expected a day from 0 to 25")]
     InvalidDay(u8),
 
-    #[error("Invalid part argument: {0}")]
+    #[error("Invalid part '{0}', expected 1 or 2")]
     InvalidPartArgument(String),
 
-    #[error("Part out of range: {0}")]
+    #[error("Invalid part {0}, expected 1 or 2")]
     PartOutOfRange(u8),
+
+    #[error("Unknown option: {0}")]
+    UnknownFlag(String),
+
+    #[error("Unexpected argument: {0}")]
+    UnexpectedArgument(String),
+
+    #[error("Invalid value for {0}: {1}")]
+    InvalidValue(&'static str, String),
+
+    #[error("Unknown parameter '{0}' for the selected days")]
+    UnknownParam(String),
+
+    #[error("{0} can't be used with {1}")]
+    ConflictingArguments(&'static str, &'static str),
+}
+
+/// Input that doesn't have the form a day expects, located like a compiler diagnostic.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    /// Day whose input failed to parse, filled in once the error leaves the day's `parse`.
+    pub day: Option<u8>,
+    /// 1-based line number, the line after the last one for an unexpected end of input.
+    pub line: usize,
+    /// 1-based column of `text` in `source`, counted in characters.
+    pub column: usize,
+    /// The offending text, empty when something is missing.
+    pub text: String,
+    /// Description of what the input should have contained instead.
+    pub expected: String,
+    /// The whole offending line, without its line break.
+    pub source: String,
+}
+
+impl ParseError {
+    /// Error for the whole `source` line.
+    pub fn new(line: usize, source: &str, expected: impl Into<String>) -> Self {
+        let source = source.trim_end_matches(['\r', '\n']);
+
+        Self {
+            day: None,
+            line,
+            column: 1,
+            text: source.to_string(),
+            expected: expected.into(),
+            source: source.to_string(),
+        }
+    }
+
+    /// Error for `text`, a part of the `source` line.
+    ///
+    /// `text` is located by its address if it's a subslice of `source`, by a search otherwise.
+    /// Text that can't be found, such as an empty string for something missing, is placed at the
+    /// end of the line.
+    pub fn at(line: usize, source: &str, text: &str, expected: impl Into<String>) -> Self {
+        let source = source.trim_end_matches(['\r', '\n']);
+        let offset = (text.as_ptr() as usize)
+            .checked_sub(source.as_ptr() as usize)
+            .filter(|offset| offset + text.len() <= source.len())
+            .or_else(|| source.find(text).filter(|_| !text.is_empty()))
+            .unwrap_or(source.len());
+
+        Self {
+            column: source[..offset].chars().count() + 1,
+            text: text.to_string(),
+            ..Self::new(line, source, expected)
+        }
+    }
+
+    /// Error for input ending before something `expected`, `line` being the line after the last.
+    pub fn eof(line: usize, expected: impl Into<String>) -> Self {
+        Self {
+            text: String::new(),
+            ..Self::new(line, "", expected)
+        }
+    }
+
+    pub fn in_day(self, day: u8) -> Self {
+        Self {
+            day: Some(day),
+            ..self
+        }
+    }
+
+    /// Renders the error the way compilers report errors in source code, pointing at the
+    /// offending text of the line. `origin` names where the input came from, e.g. its file.
+    pub fn diagnostic(&self, origin: &str) -> String {
+        let line_no = self.line.to_string();
+        let gutter = " ".repeat(line_no.len());
+
+        let found = match self.text.as_str() {
+            "" if self.source.is_empty() => "end of input".to_string(),
+            "" => "nothing".to_string(),
+            text => format!("`{text}`"),
+        };
+
+        let day = self.day.map_or(String::new(), |day| format!("day {day}: "));
+        let marker = format!(
+            "{}{}",
+            " ".repeat(self.column - 1),
+            "^".repeat(self.text.chars().count().max(1))
+        );
+
+        format!(
+            "error: {day}expected {}, found {found}\n\
+             {gutter}--> {origin}:{}:{}\n\
+             {gutter} |\n\
+             {line_no} | {}\n\
+             {gutter} | {marker}\n",
+            self.expected, self.line, self.column, self.source
+        )
+    }
 }
 
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "Day {day}: ")?;
+        }
+
+        write!(
+            f,
+            "Line {}, column {}: expected {}",
+            self.line, self.column, self.expected
+        )?;
+
+        match self.text.as_str() {
+            "" => Ok(()),
+            text => write!(f, ", found `{text}`"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
 pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SOURCE: &str = "pos=äö,12 vel=-3,4\r\n";
+
+    #[test]
+    fn at_first_column() {
+        let error = ParseError::at(1, SOURCE, &SOURCE[..3], "p");
+
+        assert_eq!((error.column, error.text.as_str()), (1, "pos"));
+        assert_eq!(error.source, "pos=äö,12 vel=-3,4");
+    }
+
+    #[test]
+    fn at_mid_line_column() {
+        let error = ParseError::at(1, SOURCE, &SOURCE[12..15], "v");
+
+        assert_eq!((error.column, error.text.as_str()), (11, "vel"));
+    }
+
+    #[test]
+    fn columns_count_characters() {
+        // `ä` and `ö` take two bytes each, the `12` after them starts at byte 9
+        let error = ParseError::at(1, SOURCE, &SOURCE[9..11], "a position");
+
+        assert_eq!((error.column, error.text.as_str()), (8, "12"));
+    }
+
+    #[test]
+    fn at_text_not_sliced_from_the_line() {
+        let text = String::from("-3");
+        assert_eq!(ParseError::at(1, SOURCE, &text, "v").column, 15);
+
+        // text that isn't in the line at all, or is empty, points past its end
+        assert_eq!(ParseError::at(1, SOURCE, "zz", "v").column, 19);
+        assert_eq!(ParseError::at(1, SOURCE, "", "v").column, 19);
+    }
+
+    #[test]
+    fn diagnostic() {
+        let error = ParseError::at(12, SOURCE, &SOURCE[9..11], "a number").in_day(14);
+
+        assert_eq!(
+            error.diagnostic("input/day_14.dat"),
+            "\
+error: day 14: expected a number, found `12`
+  --> input/day_14.dat:12:8
+   |
+12 | pos=äö,12 vel=-3,4
+   |        ^^
+"
+        );
+    }
+
+    #[test]
+    fn diagnostic_for_missing_input() {
+        let error = ParseError::eof(3, "a program");
+
+        assert_eq!(
+            error.diagnostic("<stdin>"),
+            "\
+error: expected a program, found end of input
+ --> <stdin>:3:1
+  |
+3 | \n  | ^
+"
+        );
+    }
+}
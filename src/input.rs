@@ -1,20 +1,58 @@
 use std::io::BufRead;
+use std::io::IsTerminal;
 use std::io::Read;
 
 use crate::error::{Error, Result};
 
 pub struct Input {
-    reader: std::io::BufReader<std::fs::File>,
+    reader: Box<dyn BufRead>,
+    line: usize,
 }
 
 impl Input {
+    pub fn from_reader(reader: impl BufRead + 'static) -> Input {
+        Input {
+            reader: Box::new(reader),
+            line: 0,
+        }
+    }
+
     pub fn from_file(filename: &str) -> Result<Input> {
         let reader = std::fs::File::open(filename)
             .map_err(|_| Error::InputFileNotFound(filename.to_string()))?;
 
-        Ok(Input {
-            reader: std::io::BufReader::new(reader),
-        })
+        Ok(Input::from_reader(std::io::BufReader::new(reader)))
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Input {
+        Input::from_reader(std::io::Cursor::new(bytes.to_vec()))
+    }
+
+    // an infallible constructor, so not `FromStr`
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(input: &str) -> Input {
+        Input::from_bytes(input.as_bytes())
+    }
+
+    /// Reads the input from stdin, which has to be a pipe or a redirected file.
+    ///
+    /// An interactive terminal is refused rather than waited on.
+    pub fn from_stdin() -> Result<Input> {
+        let stdin = std::io::stdin();
+        Input::from_stream(stdin.lock(), stdin.is_terminal())
+    }
+
+    fn from_stream(reader: impl BufRead + 'static, is_terminal: bool) -> Result<Input> {
+        if is_terminal {
+            return Err(Error::StdinIsTerminal);
+        }
+
+        Ok(Input::from_reader(reader))
+    }
+
+    /// Number of the line read last, 1-based. Parse errors are reported against it.
+    pub fn line_number(&self) -> usize {
+        self.line
     }
 
     pub fn read_line(&mut self) -> Option<String> {
@@ -22,21 +60,27 @@ impl Input {
         match self.reader.read_line(&mut line) {
             Ok(0) => None,
             Err(_) => None,
-            _ => Some(line),
+            _ => {
+                self.line += 1;
+                Some(line)
+            }
         }
     }
 
-    pub fn read_all(&mut self) -> String {
+    pub fn read_all(&mut self) -> Result<String> {
         let mut buffer = String::new();
-        self.reader.read_to_string(&mut buffer).unwrap();
-        buffer
+        self.reader.read_to_string(&mut buffer)?;
+        Ok(buffer)
     }
 
     pub fn read_line_as_bytes_into(&mut self, buffer: &mut Vec<u8>) -> Option<()> {
         match self.reader.read_until(b'\n', buffer) {
             Ok(0) => None,
             Err(_) => None,
-            _ => Some(()),
+            _ => {
+                self.line += 1;
+                Some(())
+            }
         }
     }
 
@@ -46,7 +90,64 @@ impl Input {
         Some(buffer)
     }
 
-    pub fn lines(self) -> std::io::Lines<std::io::BufReader<std::fs::File>> {
+    pub fn lines(self) -> std::io::Lines<Box<dyn BufRead>> {
         self.reader.lines()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::util::parse;
+
+    #[test]
+    fn line_number_counts_lines_read() {
+        let mut input = Input::from_str("a\r\n\nc");
+        assert_eq!(input.line_number(), 0);
+
+        assert_eq!(input.read_line().as_deref(), Some("a\r\n"));
+        assert_eq!(input.line_number(), 1);
+        assert_eq!(input.read_line().as_deref(), Some("\n"));
+        assert_eq!(input.line_number(), 2);
+
+        // the last line counts without a line break, the end of the input doesn't
+        assert_eq!(input.read_line().as_deref(), Some("c"));
+        assert_eq!(input.read_line(), None);
+        assert_eq!(input.line_number(), 3);
+    }
+
+    #[test]
+    fn numbered_lines() {
+        let mut input = Input::from_str("1,2\r\n\n3,4");
+        let lines = parse::lines(&mut input)
+            .map(|line| (line.line_no, line.text.clone()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            lines,
+            [
+                (1, "1,2".to_string()),
+                (2, String::new()),
+                (3, "3,4".to_string())
+            ]
+        );
+        assert_eq!(input.line_number(), 3);
+    }
+
+    #[test]
+    fn terminal_refused() {
+        let stream = || std::io::Cursor::new(b"1\n".to_vec());
+
+        assert!(matches!(
+            Input::from_stream(stream(), true),
+            Err(Error::StdinIsTerminal)
+        ));
+        assert_eq!(
+            Input::from_stream(stream(), false)
+                .unwrap()
+                .read_line()
+                .as_deref(),
+            Some("1\n")
+        );
+    }
+}
@@ -1,6 +1,13 @@
+pub mod answer;
+pub mod answers;
+pub mod bench;
 pub mod day;
 pub mod error;
 pub mod input;
+pub mod params;
+pub mod runner;
+pub mod scaffold;
+pub mod solution;
 pub mod util;
 
 pub mod day_0;
@@ -29,3 +36,18 @@ pub mod day_6;
 pub mod day_7;
 pub mod day_8;
 pub mod day_9;
+
+use solution::DynSolution;
+
+/// Registers the `Day` solutions of the day modules declared above.
+macro_rules! days {
+    ($($day:ident),* $(,)?) => {
+        static SOLUTIONS: &[&dyn DynSolution] = &[$(&$day::Day),*];
+    };
+}
+
+days! {
+    day_0, day_1, day_2, day_3, day_4, day_5, day_6, day_7, day_8, day_9, day_10, day_11, day_12,
+    day_13, day_14, day_15, day_16, day_17, day_18, day_19, day_20, day_21, day_22, day_23, day_24,
+    day_25,
+}
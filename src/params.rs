@@ -0,0 +1,141 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::error::{ArgumentError, Error, Result};
+use crate::solution::DynSolution;
+
+/// A typed puzzle parameter with its default, e.g. the size of a grid, which the examples give
+/// smaller values of than the real puzzle.
+pub struct Param<T: 'static> {
+    pub name: &'static str,
+    pub default: T,
+    /// Smallest value the day can work with, if there's a limit.
+    pub min: Option<T>,
+}
+
+impl<T: Copy> Param<T> {
+    pub const fn new(name: &'static str, default: T) -> Self {
+        Self {
+            name,
+            default,
+            min: None,
+        }
+    }
+
+    /// The same parameter rejecting values below `min`, e.g. sizes that have to be positive.
+    pub const fn at_least(self, min: T) -> Self {
+        Self {
+            min: Some(min),
+            ..self
+        }
+    }
+}
+
+/// Type erased view of a `Param`, so that a day can list all of its parameters.
+pub trait ParamSpec: Sync {
+    fn name(&self) -> &'static str;
+
+    fn default_value(&self) -> String;
+}
+
+impl<T: Display + Sync> ParamSpec for Param<T> {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn default_value(&self) -> String {
+        self.default.to_string()
+    }
+}
+
+/// Values overriding the defaults of parameters, by parameter name.
+#[derive(Clone, Debug, Default)]
+pub struct Params {
+    values: Vec<(String, String)>,
+}
+
+impl Params {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Overrides the parameter `name`, a later value replaces an earlier one.
+    pub fn set(&mut self, name: &str, value: impl Display) {
+        self.values.retain(|(n, _)| n != name);
+        self.values.push((name.to_string(), value.to_string()));
+    }
+
+    pub fn with(mut self, name: &str, value: impl Display) -> Self {
+        self.set(name, value);
+        self
+    }
+
+    /// Parses a `name=value` override as given on the command line.
+    pub fn set_from_arg(&mut self, arg: &str) -> Result<(), ArgumentError> {
+        match arg.split_once('=') {
+            Some((name, value)) if !name.is_empty() => {
+                self.set(name, value);
+                Ok(())
+            }
+            _ => Err(ArgumentError::InvalidValue("--param", arg.to_string())),
+        }
+    }
+
+    /// Value of `param`, its default unless overridden.
+    pub fn get<T: FromStr + Copy + PartialOrd>(&self, param: &Param<T>) -> Result<T> {
+        let Some((_, value)) = self.values.iter().find(|(name, _)| name == param.name) else {
+            return Ok(param.default);
+        };
+
+        match value.parse() {
+            Ok(parsed) if param.min.is_none_or(|min| parsed >= min) => Ok(parsed),
+            _ => Err(Error::InvalidParam(param.name, value.clone())),
+        }
+    }
+
+    /// The first overridden parameter none of the `solutions` declares.
+    pub fn find_unknown(&self, solutions: &[&dyn DynSolution]) -> Option<&str> {
+        self.values
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .find(|name| {
+                !solutions
+                    .iter()
+                    .flat_map(|solution| solution.params())
+                    .any(|param| param.name() == *name)
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SIZE: Param<i64> = Param::new("size", 10).at_least(1);
+
+    #[test]
+    fn default_unless_overridden() {
+        assert_eq!(Params::new().get(&SIZE).unwrap(), 10);
+        assert_eq!(Params::new().with("size", 3).get(&SIZE).unwrap(), 3);
+    }
+
+    #[test]
+    fn values_below_the_minimum() {
+        assert_eq!(Params::new().with("size", 1).get(&SIZE).unwrap(), 1);
+
+        for value in ["0", "-5"] {
+            assert!(matches!(
+                Params::new().with("size", value).get(&SIZE),
+                Err(Error::InvalidParam("size", v)) if v == value
+            ));
+        }
+    }
+
+    #[test]
+    fn values_that_dont_parse() {
+        assert!(matches!(
+            Params::new().with("size", "ten").get(&SIZE),
+            Err(Error::InvalidParam("size", _))
+        ));
+    }
+}
@@ -0,0 +1,342 @@
+use std::any::Any;
+use std::fmt::{self, Display, Formatter};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, Once};
+use std::time::{Duration, Instant};
+
+use crate::answer::Answer;
+use crate::day;
+use crate::error::{Error, Result};
+use crate::input::Input;
+use crate::params::Params;
+use crate::solution::DynSolution;
+use crate::util::Table;
+
+pub struct PartRun {
+    pub part: day::Part,
+    pub answer: Result<Answer>,
+    pub solve_time: Duration,
+}
+
+pub struct DayRun {
+    pub day: u8,
+    pub parse_time: Duration,
+    pub parts: Vec<PartRun>,
+}
+
+/// Whether panics are being caught by `catch_panic`, which reports them instead of the hook.
+static CATCHING: AtomicBool = AtomicBool::new(false);
+
+/// Location of the last panic, kept by the panic hook for `catch_panic`.
+static PANIC_LOCATION: Mutex<Option<String>> = Mutex::new(None);
+
+fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+
+    INSTALL.call_once(|| {
+        let default_hook = panic::take_hook();
+
+        panic::set_hook(Box::new(move |info| {
+            if let Some(location) = info.location() {
+                *PANIC_LOCATION.lock().unwrap_or_else(|e| e.into_inner()) =
+                    Some(location.to_string());
+            }
+
+            if !CATCHING.load(Ordering::Relaxed) {
+                default_hook(info);
+            }
+        }));
+    });
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+/// Runs `f`, turning a panic in it, or in the threads it joins, into an `Error::Panic` with the
+/// panic message and location.
+pub fn catch_panic<T>(f: impl FnOnce() -> Result<T>) -> Result<T> {
+    install_panic_hook();
+
+    let was_catching = CATCHING.swap(true, Ordering::Relaxed);
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.store(was_catching, Ordering::Relaxed);
+
+    result.unwrap_or_else(|payload| {
+        let location = PANIC_LOCATION
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+            .unwrap_or_else(|| "unknown location".to_string());
+
+        Err(Error::Panic(panic_message(payload.as_ref()), location))
+    })
+}
+
+/// Parses the input once and solves each of `parts` against the parsed model.
+///
+/// A failing part doesn't stop the remaining ones, its error is kept in its `PartRun`. Panics
+/// are caught and reported as errors of the parse or of the part that panicked.
+pub fn run(
+    solution: &dyn DynSolution,
+    input: Input,
+    params: &Params,
+    parts: &[day::Part],
+) -> Result<DayRun> {
+    let start = Instant::now();
+    let model = catch_panic(|| solution.parse(input, params))?;
+    let parse_time = start.elapsed();
+
+    let parts = parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let answer = catch_panic(|| solution.solve(model.as_ref(), part));
+
+            PartRun {
+                part,
+                answer,
+                solve_time: start.elapsed(),
+            }
+        })
+        .collect();
+
+    Ok(DayRun {
+        day: solution.day(),
+        parse_time,
+        parts,
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Status {
+    Ok,
+    Correct,
+    Wrong,
+    Unchecked,
+    NotImplemented,
+    Failed,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Status::Ok => write!(f, "ok"),
+            Status::Correct => write!(f, "correct"),
+            Status::Wrong => write!(f, "wrong"),
+            Status::Unchecked => write!(f, "no answer"),
+            Status::NotImplemented => write!(f, "not implemented"),
+            Status::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+struct SummaryRow {
+    day: u8,
+    part: day::Part,
+    answer: String,
+    status: Status,
+    parse_time: Option<Duration>,
+    solve_time: Option<Duration>,
+}
+
+/// Table of answers for a run over several days and parts.
+#[derive(Default)]
+pub struct Summary {
+    rows: Vec<SummaryRow>,
+}
+
+impl Summary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, day_run: DayRun) {
+        for part_run in day_run.parts {
+            let (answer, status) = match part_run.answer {
+                Ok(answer) => (answer.to_string(), Status::Ok),
+                Err(e) => (e.to_string(), Status::Failed),
+            };
+
+            self.rows.push(SummaryRow {
+                day: day_run.day,
+                part: part_run.part,
+                answer,
+                status,
+                parse_time: Some(day_run.parse_time),
+                solve_time: Some(part_run.solve_time),
+            });
+        }
+    }
+
+    pub fn add_not_implemented(&mut self, day: u8, parts: &[day::Part]) {
+        self.add_without_run(day, parts, String::new(), Status::NotImplemented);
+    }
+
+    /// Records a day that couldn't be run at all, e.g. because its input failed to parse.
+    pub fn add_failed(&mut self, day: u8, parts: &[day::Part], error: &Error) {
+        self.add_without_run(day, parts, error.to_string(), Status::Failed);
+    }
+
+    fn add_without_run(&mut self, day: u8, parts: &[day::Part], answer: String, status: Status) {
+        for &part in parts {
+            self.rows.push(SummaryRow {
+                day,
+                part,
+                answer: answer.clone(),
+                status,
+                parse_time: None,
+                solve_time: None,
+            });
+        }
+    }
+
+    /// Compares the answers of `day` with the `expected` ones, marking them correct or wrong, or
+    /// unchecked when there is no expected one.
+    pub fn check(&mut self, day: u8, expected: impl Fn(day::Part) -> Option<String>) {
+        let rows = self
+            .rows
+            .iter_mut()
+            .filter(|row| row.day == day && row.status == Status::Ok);
+
+        for row in rows {
+            match expected(row.part) {
+                Some(expected) if expected == row.answer => row.status = Status::Correct,
+                Some(expected) => {
+                    row.answer = format!("{} (expected {expected})", row.answer);
+                    row.status = Status::Wrong;
+                }
+                None => row.status = Status::Unchecked,
+            }
+        }
+    }
+
+    pub fn count(&self, status: Status) -> usize {
+        self.rows.iter().filter(|row| row.status == status).count()
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let time = |t: Option<Duration>| t.map_or(String::new(), |t| format!("{t:.2?}"));
+
+        let mut table = Table::new(["Day", "Part", "Answer", "Status", "Parse", "Solve"]);
+        for row in &self.rows {
+            table.push([
+                row.day.to_string(),
+                row.part.to_string(),
+                row.answer.clone(),
+                row.status.to_string(),
+                time(row.parse_time),
+                time(row.solve_time),
+            ]);
+        }
+
+        write!(f, "{table}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::util::parallel;
+
+    /// The panic location is global, so the tests that panic take turns.
+    static PANICKING: Mutex<()> = Mutex::new(());
+
+    fn panics_at(result: Result<()>, message: &str) -> bool {
+        matches!(
+            result,
+            Err(Error::Panic(m, location)) if m == message && location.starts_with("src/runner.rs:")
+        )
+    }
+
+    #[test]
+    fn check_against_expected_answers() {
+        let part = |part, answer: Result<Answer>| PartRun {
+            part,
+            answer,
+            solve_time: Duration::ZERO,
+        };
+
+        let mut summary = Summary::new();
+        for (day, answers) in [
+            (1, [Ok(Answer::Int(1)), Ok(Answer::Int(2))]),
+            (
+                2,
+                [
+                    Ok(Answer::Int(3)),
+                    Err(Error::NoSolution("none".to_string())),
+                ],
+            ),
+        ] {
+            let [one, two] = answers;
+            summary.add(DayRun {
+                day,
+                parse_time: Duration::ZERO,
+                parts: vec![part(day::Part::One, one), part(day::Part::Two, two)],
+            });
+        }
+
+        summary.check(1, |part| match part {
+            day::Part::One => Some("1".to_string()),
+            day::Part::Two => Some("3".to_string()),
+        });
+        summary.check(2, |_| None);
+
+        assert_eq!(summary.count(Status::Correct), 1);
+        assert_eq!(summary.count(Status::Wrong), 1);
+        assert_eq!(summary.count(Status::Unchecked), 1);
+        assert_eq!(summary.count(Status::Failed), 1);
+        assert_eq!(summary.count(Status::Ok), 0);
+        assert!(summary.to_string().contains("2 (expected 3)"));
+    }
+
+    #[test]
+    fn results_pass_through() {
+        assert!(matches!(catch_panic(|| Ok(42)), Ok(42)));
+        assert!(matches!(
+            catch_panic(|| Err::<(), _>(Error::NoSolution("none".to_string()))),
+            Err(Error::NoSolution(_))
+        ));
+    }
+
+    #[test]
+    fn panic_becomes_an_error() {
+        let _turn = PANICKING.lock().unwrap_or_else(|e| e.into_inner());
+
+        assert!(panics_at(catch_panic(|| panic!("boom")), "boom"));
+
+        let index = 3;
+        let result = catch_panic(|| {
+            let items: Vec<u8> = Vec::new();
+            assert!(index < items.len(), "index {index} out of range");
+            Ok(())
+        });
+        assert!(panics_at(result, "index 3 out of range"));
+    }
+
+    #[test]
+    fn panic_in_a_worker_thread() {
+        let _turn = PANICKING.lock().unwrap_or_else(|e| e.into_inner());
+
+        let result = parallel::test::with_threads(4, || {
+            catch_panic(|| {
+                parallel::map(&[1, 2, 3, 0, 5, 6, 7, 8], |&n| {
+                    assert!(n != 0, "worker got a zero");
+                    n
+                });
+                Ok(())
+            })
+        });
+
+        assert!(panics_at(result, "worker got a zero"));
+    }
+}
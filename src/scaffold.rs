@@ -0,0 +1,197 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+
+/// The module new days start from.
+const TEMPLATE: &str = include_str!("day_0.rs");
+
+/// Width the `days!` list in `lib.rs` is wrapped at, the same as rustfmt's.
+const MAX_WIDTH: usize = 100;
+
+/// Creates `src/day_<day>.rs` from the `day_0` template, declares and registers it in
+/// `src/lib.rs` and creates an empty example input for it, all relative to the repository
+/// `root`. Returns the files it wrote.
+pub fn new_day(root: &Path, day: u8) -> Result<Vec<PathBuf>> {
+    let module = format!("day_{day}");
+    let module_path = root.join("src").join(format!("{module}.rs"));
+    let lib_path = root.join("src").join("lib.rs");
+
+    if module_path.exists() {
+        return Err(Error::DayExists(day, module_path.display().to_string()));
+    }
+
+    let lib = fs::read_to_string(&lib_path)
+        .map_err(|_| Error::Scaffold(format!("{} not found", lib_path.display())))?;
+    let lib = register(&lib, &module)?;
+
+    let source = TEMPLATE
+        .replace("day_0", &module)
+        .replace("const DAY: u8 = 0;", &format!("const DAY: u8 = {day};"))
+        .replace("\"Template\"", &format!("\"Day {day}\""));
+
+    fs::write(&module_path, source)?;
+    fs::write(&lib_path, lib)?;
+    let mut written = vec![module_path, lib_path];
+
+    let example_path = root.join("input").join(format!("{module}-example.dat"));
+    if !example_path.exists() {
+        fs::create_dir_all(root.join("input"))?;
+        fs::write(&example_path, "")?;
+        written.push(example_path);
+    }
+
+    Ok(written)
+}
+
+/// Declares `module` next to the other day modules of the `lib.rs` source and adds it to the
+/// `days!` list, keeping both in the order rustfmt keeps them.
+fn register(lib: &str, module: &str) -> Result<String> {
+    let lib = add_to_days(lib, module)?;
+    declare(&lib, module)
+}
+
+/// Inserts `pub mod <module>;` into the block of day module declarations, sorted like rustfmt
+/// sorts them.
+fn declare(lib: &str, module: &str) -> Result<String> {
+    let declaration = format!("pub mod {module};");
+    let is_day = |line: &str| line.starts_with("pub mod day_");
+
+    let mut lines = lib.lines().collect::<Vec<_>>();
+    let first = lines
+        .iter()
+        .position(|line| is_day(line))
+        .ok_or_else(|| Error::Scaffold("day modules not found in lib.rs".to_string()))?;
+    let count = lines[first..]
+        .iter()
+        .take_while(|line| is_day(line))
+        .count();
+    let at = lines[first..first + count]
+        .partition_point(|line| line.trim_end_matches(';') < declaration.trim_end_matches(';'));
+    lines.insert(first + at, &declaration);
+
+    let mut lib = lines.join("\n");
+    lib.push('\n');
+    Ok(lib)
+}
+
+/// Adds `module` to the `days!` list of the `lib.rs` source, keeping the list ordered by day.
+fn add_to_days(lib: &str, module: &str) -> Result<String> {
+    let not_found = || Error::Scaffold("days! list not found in lib.rs".to_string());
+
+    let start = lib.find("\ndays! {\n").ok_or_else(not_found)? + "\ndays! {\n".len();
+    let end = start + lib[start..].find("\n}").ok_or_else(not_found)?;
+
+    let day_number = |module: &str| {
+        module
+            .strip_prefix("day_")
+            .and_then(|day| day.parse::<u8>().ok())
+    };
+
+    let mut days = lib[start..end]
+        .split(',')
+        .map(str::trim)
+        .filter(|day| !day.is_empty())
+        .collect::<Vec<_>>();
+
+    if days.contains(&module) {
+        return Err(Error::Scaffold(format!(
+            "{module} is already registered in lib.rs"
+        )));
+    }
+    days.push(module);
+    days.sort_by_key(|&day| day_number(day));
+
+    let mut list = String::new();
+    let mut line = String::from("   ");
+    for day in days {
+        if line.len() + day.len() + 2 > MAX_WIDTH {
+            list.push_str(&line);
+            list.push('\n');
+            line = String::from("   ");
+        }
+        line.push(' ');
+        line.push_str(day);
+        line.push(',');
+    }
+    list.push_str(&line);
+
+    Ok(format!("{}{list}{}", &lib[..start], &lib[end..]))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const LIB: &str = "\
+pub mod day;
+
+pub mod day_1;
+pub mod day_10;
+
+days! {
+    day_1, day_10,
+}
+
+pub fn after() {}
+";
+
+    #[test]
+    fn keeps_days_ordered() {
+        let lib = register(LIB, "day_7").unwrap();
+        assert_eq!(
+            lib,
+            LIB.replace("day_1, day_10,", "day_1, day_7, day_10,")
+                .replace("pub mod day_10;\n", "pub mod day_10;\npub mod day_7;\n")
+        );
+
+        let lib = register(&lib, "day_0").unwrap();
+        assert!(lib.contains("\n    day_0, day_1, day_7, day_10,\n}\n\npub fn after() {}\n"));
+
+        let lib = register(&lib, "day_25").unwrap();
+        assert!(lib.contains("day_10, day_25,\n}"));
+        assert!(lib.contains(
+            "pub mod day_0;\npub mod day_1;\npub mod day_10;\npub mod day_25;\npub mod day_7;\n"
+        ));
+    }
+
+    #[test]
+    fn wraps_like_rustfmt() {
+        let lib = include_str!("lib.rs");
+        let without_last = lib
+            .replace("\n    day_25,", "")
+            .replace("pub mod day_25;\n", "");
+        assert_ne!(without_last, lib);
+
+        assert_eq!(register(&without_last, "day_25").unwrap(), lib);
+
+        let registered = register(lib, "day_26").unwrap();
+        assert!(registered.lines().all(|line| line.len() <= MAX_WIDTH));
+        assert!(registered.contains("    day_25, day_26,\n}"));
+        assert!(registered.contains("pub mod day_25;\npub mod day_26;\npub mod day_3;\n"));
+    }
+
+    #[test]
+    fn already_registered() {
+        assert!(matches!(
+            register(LIB, "day_10"),
+            Err(Error::Scaffold(message)) if message.contains("day_10 is already registered")
+        ));
+    }
+
+    #[test]
+    fn no_days_list() {
+        assert!(matches!(
+            register("pub mod day;\n", "day_3"),
+            Err(Error::Scaffold(_))
+        ));
+    }
+
+    #[test]
+    fn no_day_modules() {
+        assert!(matches!(
+            register("pub mod day;\n\ndays! {\n    day_1,\n}\n", "day_3"),
+            Err(Error::Scaffold(message)) if message.contains("day modules not found")
+        ));
+    }
+}
@@ -0,0 +1,87 @@
+use std::any::Any;
+
+use crate::answer::Answer;
+use crate::day;
+use crate::error::{Error, Result};
+use crate::input::Input;
+use crate::params::{ParamSpec, Params};
+
+/// A puzzle solution for a single day.
+///
+/// The input is parsed into `Model` once, both parts are then solved against the same model.
+/// Puzzle parameters the day declares in `PARAMS` are read while parsing and kept in the model.
+pub trait Solution {
+    type Model: 'static;
+
+    const DAY: u8;
+    const TITLE: &'static str;
+    const PARAMS: &'static [&'static dyn ParamSpec] = &[];
+
+    fn parse(input: Input, params: &Params) -> Result<Self::Model>;
+
+    fn part_one(model: &Self::Model) -> Result<Answer>;
+
+    fn part_two(model: &Self::Model) -> Result<Answer>;
+}
+
+/// Object safe view of a `Solution`, so that all the days can live in one registry.
+pub trait DynSolution: Sync {
+    fn day(&self) -> u8;
+
+    fn title(&self) -> &'static str;
+
+    fn params(&self) -> &'static [&'static dyn ParamSpec];
+
+    /// Parses the input into the day's model, type erased so it can be passed back to `solve`.
+    fn parse(&self, input: Input, params: &Params) -> Result<Box<dyn Any>>;
+
+    /// Solves `part` against a model returned from `parse` of the same day.
+    fn solve(&self, model: &dyn Any, part: day::Part) -> Result<Answer>;
+
+    fn run(&self, input: Input, params: &Params, part: day::Part) -> Result<Answer> {
+        let model = self.parse(input, params)?;
+        self.solve(model.as_ref(), part)
+    }
+}
+
+impl<S: Solution + Sync> DynSolution for S {
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn title(&self) -> &'static str {
+        S::TITLE
+    }
+
+    fn params(&self) -> &'static [&'static dyn ParamSpec] {
+        S::PARAMS
+    }
+
+    fn parse(&self, input: Input, params: &Params) -> Result<Box<dyn Any>> {
+        match S::parse(input, params) {
+            Ok(model) => Ok(Box::new(model)),
+            Err(Error::Parse(e)) => Err(Error::Parse(e.in_day(S::DAY))),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn solve(&self, model: &dyn Any, part: day::Part) -> Result<Answer> {
+        let model = model
+            .downcast_ref::<S::Model>()
+            .expect("model parsed by the same day");
+
+        match part {
+            day::Part::One => S::part_one(model),
+            day::Part::Two => S::part_two(model),
+        }
+    }
+}
+
+/// All the registered days, ordered by day number.
+pub fn all() -> &'static [&'static dyn DynSolution] {
+    crate::SOLUTIONS
+}
+
+pub fn find(day: u8) -> Option<&'static dyn DynSolution> {
+    all().iter().copied().find(|solution| solution.day() == day)
+}
@@ -1,5 +1,7 @@
 use crate::day;
 use crate::error::ArgumentError;
+use crate::params::Params;
+use crate::solution;
 
 pub fn validate_day(day: u8) -> Result<u8, ArgumentError> {
     if day > 25 {
@@ -15,6 +17,29 @@ pub fn parse_day(arg: impl AsRef<str>) -> Result<u8, ArgumentError> {
         .map_err(|_| ArgumentError::InvalidDayInput(day.to_string()))
 }
 
+/// Parses a single day (`7`), an inclusive range of days (`5..=12`) or `all` days of the calendar.
+pub fn parse_days(arg: impl AsRef<str>) -> Result<Vec<u8>, ArgumentError> {
+    let days = arg.as_ref();
+
+    if days == "all" {
+        return Ok((1..=25).collect());
+    }
+
+    match days.split_once("..=") {
+        Some((first, last)) => {
+            let first = parse_day(first).and_then(validate_day)?;
+            let last = parse_day(last).and_then(validate_day)?;
+
+            if first > last {
+                Err(ArgumentError::InvalidDayInput(days.to_string()))?
+            }
+
+            Ok((first..=last).collect())
+        }
+        None => parse_day(days).and_then(validate_day).map(|day| vec![day]),
+    }
+}
+
 pub fn validate_part(part: u8) -> Result<day::Part, ArgumentError> {
     match part {
         1 => Ok(day::Part::One),
@@ -28,6 +53,20 @@ pub fn parse_part(arg: String) -> Result<u8, ArgumentError> {
         .map_err(|_| ArgumentError::InvalidPartArgument(arg))
 }
 
+pub fn parse_bench_runs(arg: String) -> Result<usize, ArgumentError> {
+    match arg.parse::<usize>() {
+        Ok(runs) if runs > 0 => Ok(runs),
+        _ => Err(ArgumentError::InvalidValue("--bench", arg)),
+    }
+}
+
+pub fn parse_threads(arg: String) -> Result<usize, ArgumentError> {
+    match arg.parse::<usize>() {
+        Ok(threads) if threads > 0 => Ok(threads),
+        _ => Err(ArgumentError::InvalidValue("--threads", arg)),
+    }
+}
+
 pub fn construct_filename(day: u8, part: day::Part) -> String {
     let part = match part {
         day::Part::One => "1",
@@ -35,3 +74,373 @@ pub fn construct_filename(day: u8, part: day::Part) -> String {
     };
     format!("input/day_{day}-{part}.dat")
 }
+
+pub fn construct_example_filename(day: u8) -> String {
+    format!("input/day_{day}-example.dat")
+}
+
+/// Command line arguments of the binary.
+#[derive(Debug, Default)]
+pub struct Args {
+    pub days: Vec<u8>,
+    pub part: Option<day::Part>,
+    pub input: Option<String>,
+    pub example: bool,
+    pub stdin: bool,
+    pub verbose: bool,
+    pub help: bool,
+    pub bench: Option<usize>,
+    pub bench_output: Option<String>,
+    pub params: Params,
+    pub check: bool,
+    pub record: bool,
+    pub answers: Option<String>,
+    pub threads: Option<usize>,
+    /// Day to create from the template, given as `new <DAY>`.
+    pub new: Option<u8>,
+}
+
+impl Args {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Args, ArgumentError> {
+        let mut parsed = Args::default();
+        let mut days = None;
+        let mut new = false;
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            // both `--flag value` and `--flag=value` are accepted
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+                _ => (arg.as_str(), None),
+            };
+
+            let mut value = |flag: &'static str| {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or(ArgumentError::MissingValue(flag))
+            };
+
+            match flag {
+                "-d" | "--day" => days = Some(parse_days(value("--day")?)?),
+                "-p" | "--part" => {
+                    parsed.part = Some(parse_part(value("--part")?).and_then(validate_part)?)
+                }
+                "-i" | "--input" => match value("--input")?.as_str() {
+                    "-" => parsed.stdin = true,
+                    input => parsed.input = Some(input.to_string()),
+                },
+                "-e" | "--example" => parsed.example = true,
+                "-" | "--stdin" => parsed.stdin = true,
+                "-v" | "--verbose" => parsed.verbose = true,
+                "-h" | "--help" => parsed.help = true,
+                "--bench" => parsed.bench = Some(parse_bench_runs(value("--bench")?)?),
+                "--bench-output" => parsed.bench_output = Some(value("--bench-output")?),
+                "--param" => parsed.params.set_from_arg(&value("--param")?)?,
+                "--check" => parsed.check = true,
+                "--record" => parsed.record = true,
+                "--answers" => parsed.answers = Some(value("--answers")?),
+                "--threads" => parsed.threads = Some(parse_threads(value("--threads")?)?),
+                flag if flag.starts_with('-') && flag.len() > 1 => {
+                    Err(ArgumentError::UnknownFlag(flag.to_string()))?
+                }
+                "new" if days.is_none() && !new => new = true,
+                _ if new && days.is_none() => {
+                    days = Some(vec![parse_day(&arg).and_then(validate_day)?])
+                }
+                _ if days.is_none() => days = Some(parse_days(&arg)?),
+                _ => Err(ArgumentError::UnexpectedArgument(arg))?,
+            }
+        }
+
+        if parsed.help {
+            return Ok(parsed);
+        }
+
+        parsed.days = days.ok_or(ArgumentError::MissingArgument("day"))?;
+
+        if new {
+            parsed.new = Some(parsed.days[0]);
+            return Ok(parsed);
+        }
+
+        let sources = [
+            ("--input", parsed.input.is_some()),
+            ("--example", parsed.example),
+            ("--stdin", parsed.stdin),
+        ]
+        .into_iter()
+        .filter_map(|(flag, given)| given.then_some(flag))
+        .collect::<Vec<_>>();
+
+        if let [first, second, ..] = sources[..] {
+            Err(ArgumentError::ConflictingArguments(first, second))?
+        }
+
+        // a single input file can't be shared by several days
+        if parsed.input.is_some() && parsed.days.len() > 1 {
+            Err(ArgumentError::ConflictingArguments(
+                "--input",
+                "several days",
+            ))?
+        }
+
+        // stdin can only be read once
+        if parsed.stdin && parsed.days.len() > 1 {
+            Err(ArgumentError::ConflictingArguments(
+                "--stdin",
+                "several days",
+            ))?
+        }
+
+        if parsed.stdin && parsed.bench.is_some() {
+            Err(ArgumentError::ConflictingArguments("--stdin", "--bench"))?
+        }
+
+        let modes = [
+            ("--bench", parsed.bench.is_some()),
+            ("--check", parsed.check),
+            ("--record", parsed.record),
+        ]
+        .into_iter()
+        .filter_map(|(flag, given)| given.then_some(flag))
+        .collect::<Vec<_>>();
+
+        if let [first, second, ..] = modes[..] {
+            Err(ArgumentError::ConflictingArguments(first, second))?
+        }
+
+        Ok(parsed)
+    }
+
+    /// Parts to run, both unless a single part was asked for.
+    pub fn parts(&self) -> Vec<day::Part> {
+        match self.part {
+            Some(part) => vec![part],
+            None => vec![day::Part::One, day::Part::Two],
+        }
+    }
+
+    /// Input file of `day` when not reading stdin. Parts that are run together share the input
+    /// file of the first one.
+    pub fn input_file(&self, day: u8) -> String {
+        match &self.input {
+            Some(input) => input.clone(),
+            None if self.example => construct_example_filename(day),
+            None => construct_filename(day, self.parts()[0]),
+        }
+    }
+}
+
+pub fn usage() -> String {
+    let mut usage = String::from(
+        "\
+Usage: aoc2024 [OPTIONS] [DAYS]
+       aoc2024 new <DAY>
+
+Runs the solutions of the given days and prints their answers. A single day and part prints
+just the answer, anything else prints a summary table.
+
+Arguments:
+  [DAYS]  A day, an inclusive range of days like 5..=12, or all
+
+Commands:
+  new <DAY>  Create src/day_<DAY>.rs from the day_0 template, register it in src/lib.rs and
+             create an empty input/day_<DAY>-example.dat, run from the repository root
+
+Options:
+  -d, --day <DAYS>             Same as the DAYS argument
+  -p, --part <PART>            Part to run, 1 or 2, both parts if omitted
+  -i, --input <FILE>           Input file, - for stdin [default: input/day_<DAY>-<PART>.dat]
+  -e, --example                Use the example input input/day_<DAY>-example.dat
+  -,  --stdin                  Read the input from a pipe or redirected stdin, same as --input -
+      --bench <N>              Parse and solve each part N times and report the timings
+      --bench-output <FILE>    CSV file benchmark results are appended to [default: bench.csv]
+      --param <NAME=VALUE>     Override a puzzle parameter, e.g. --param width=7, can be repeated
+      --check                  Compare the answers with the answers file, fail unless all match
+      --record                 Write the answers to the answers file
+      --answers <FILE>         Answers file of --check and --record [default: answers.txt]
+      --threads <N>            Threads of the days that solve in parallel [default: all cores]
+  -v, --verbose                Log progress, same as RUST_LOG=info
+  -h, --help                   Print this help
+
+Logging:
+  Solutions log under <day>::<category> targets, where the categories are progress (info),
+  render (debug) and trace (trace). E.g. RUST_LOG=day_17=trace logs everything of day 17,
+  RUST_LOG=day_16::render=debug draws the maze of day 16. With AOC_RENDER_DIR set, rendered
+  grids are also saved to that directory as PPM images, and simulations (days 14 and 15) as
+  asciicast recordings and animated GIFs. AOC_FRAME_EVERY=n records every n-th step only and
+  AOC_MAX_FRAMES=n keeps the last n frames (300 by default).
+
+Days:
+",
+    );
+
+    for solution in solution::all() {
+        usage += &format!("  {:>2}  {}", solution.day(), solution.title());
+
+        let params = solution
+            .params()
+            .iter()
+            .map(|param| format!("{}={}", param.name(), param.default_value()))
+            .collect::<Vec<_>>();
+
+        if !params.is_empty() {
+            usage += &format!(" [{}]", params.join(", "));
+        }
+        usage += "\n";
+    }
+
+    usage
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::params::Param;
+
+    fn parse(args: &str) -> Result<Args, ArgumentError> {
+        Args::parse(args.split_whitespace().map(str::to_string))
+    }
+
+    #[test]
+    fn flag_values_inline_or_separate() {
+        let width = Param::new("width", 0);
+
+        for args in [
+            "7 --part 2 --input in.txt --param width=5",
+            "7 --part=2 --input=in.txt --param=width=5",
+            "-p 2 -i in.txt --day=7 --param width=5",
+        ] {
+            let parsed = parse(args).unwrap();
+
+            assert_eq!(parsed.days, [7]);
+            assert!(matches!(parsed.part, Some(day::Part::Two)));
+            assert_eq!(parsed.input.as_deref(), Some("in.txt"));
+            assert_eq!(parsed.params.get(&width).unwrap(), 5);
+        }
+
+        assert!(matches!(
+            parse("7 --part"),
+            Err(ArgumentError::MissingValue("--part"))
+        ));
+        assert!(matches!(
+            parse("7 -p=2"),
+            Err(ArgumentError::UnknownFlag(f)) if f == "-p=2"
+        ));
+    }
+
+    #[test]
+    fn day_ranges() {
+        assert_eq!(parse("5..=8").unwrap().days, [5, 6, 7, 8]);
+        assert_eq!(parse("--day 3..=3").unwrap().days, [3]);
+        assert_eq!(parse("all").unwrap().days, (1..=25).collect::<Vec<_>>());
+
+        assert!(matches!(
+            parse("8..=5"),
+            Err(ArgumentError::InvalidDayInput(d)) if d == "8..=5"
+        ));
+        assert!(matches!(
+            parse("5..=26"),
+            Err(ArgumentError::InvalidDay(26))
+        ));
+        assert!(matches!(
+            parse("five"),
+            Err(ArgumentError::InvalidDayInput(_))
+        ));
+        assert!(matches!(
+            parse("--example"),
+            Err(ArgumentError::MissingArgument("day"))
+        ));
+        assert!(matches!(
+            parse("5 6"),
+            Err(ArgumentError::UnexpectedArgument(a)) if a == "6"
+        ));
+    }
+
+    #[test]
+    fn parts() {
+        assert!(matches!(
+            parse("7 -p 3"),
+            Err(ArgumentError::PartOutOfRange(3))
+        ));
+        assert!(matches!(
+            parse("7 -p one"),
+            Err(ArgumentError::InvalidPartArgument(p)) if p == "one"
+        ));
+        assert_eq!(parse("7").unwrap().parts().len(), 2);
+        assert_eq!(
+            parse("7 -e").unwrap().input_file(7),
+            "input/day_7-example.dat"
+        );
+        assert_eq!(parse("7 -p 2").unwrap().input_file(7), "input/day_7-2.dat");
+    }
+
+    #[test]
+    fn new_day() {
+        let parsed = parse("new 12").unwrap();
+        assert_eq!(parsed.new, Some(12));
+
+        assert!(matches!(
+            parse("new 26"),
+            Err(ArgumentError::InvalidDay(26))
+        ));
+        assert!(matches!(
+            parse("new 1..=3"),
+            Err(ArgumentError::InvalidDayInput(_))
+        ));
+        assert!(matches!(
+            parse("new"),
+            Err(ArgumentError::MissingArgument("day"))
+        ));
+        assert_eq!(
+            parse("5 new").unwrap_err().to_string(),
+            "Unexpected argument: new"
+        );
+    }
+
+    #[test]
+    fn conflicting_flags() {
+        let conflicts = [
+            ("7 -i in.txt -e", "--input", "--example"),
+            ("7 --stdin -e", "--example", "--stdin"),
+            ("1..=2 -i in.txt", "--input", "several days"),
+            ("all -", "--stdin", "several days"),
+            ("7 - --bench 3", "--stdin", "--bench"),
+            ("7 --check --record", "--check", "--record"),
+            ("7 --bench 3 --check", "--bench", "--check"),
+        ];
+
+        for (args, first, second) in conflicts {
+            assert!(
+                matches!(
+                    parse(args),
+                    Err(ArgumentError::ConflictingArguments(a, b)) if a == first && b == second
+                ),
+                "{args}"
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_options() {
+        assert!(matches!(
+            parse("7 --verbatim"),
+            Err(ArgumentError::UnknownFlag(f)) if f == "--verbatim"
+        ));
+        assert!(matches!(
+            parse("7 --threads 0"),
+            Err(ArgumentError::InvalidValue("--threads", _))
+        ));
+        assert!(matches!(
+            parse("7 --bench none"),
+            Err(ArgumentError::InvalidValue("--bench", _))
+        ));
+        assert!(matches!(
+            parse("7 --param width"),
+            Err(ArgumentError::InvalidValue("--param", _))
+        ));
+        assert!(parse("--help --verbatim").is_err());
+        assert!(parse("--help").unwrap().help);
+    }
+}
@@ -0,0 +1,146 @@
+//! Cooperative limits on long-running loops, so that an input a solution doesn't expect makes it
+//! fail instead of spinning forever.
+//!
+//! A day that bounds a loop declares the `TIMEOUT` and `MAX_STEPS` parameters, keeps the
+//! `Budget` they set and starts a `Meter` for the loop, which the loop then steps once per
+//! iteration. E.g. `--param max_steps=1000` stops the loop of each day that declares it after
+//! 1000 iterations.
+
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, Result};
+use crate::params::{Param, Params};
+
+/// Seconds the bounded loop of a day may run, 0 for no limit.
+pub const TIMEOUT: Param<f64> = Param::new("timeout", 0.0).at_least(0.0);
+
+/// Iterations the bounded loop of a day may take, 0 for no limit.
+pub const MAX_STEPS: Param<u64> = Param::new("max_steps", 0);
+
+/// Meters only look at the clock every this many steps, it's slow compared to tight loops.
+const CLOCK_EVERY: u64 = 64;
+
+/// Wall time and step count a loop may take, unlimited unless set.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Budget {
+    pub time: Option<Duration>,
+    pub steps: Option<u64>,
+}
+
+impl Budget {
+    /// The budget set by the `TIMEOUT` and `MAX_STEPS` parameters.
+    pub fn from_params(params: &Params) -> Result<Self> {
+        let secs = params.get(&TIMEOUT)?;
+        let time = Duration::try_from_secs_f64(secs)
+            .map_err(|_| Error::InvalidParam(TIMEOUT.name, secs.to_string()))?;
+        let steps = params.get(&MAX_STEPS)?;
+
+        Ok(Self {
+            time: (!time.is_zero()).then_some(time),
+            steps: (steps > 0).then_some(steps),
+        })
+    }
+
+    /// Starts metering the loop called `name`, which names it in the error once it runs out.
+    pub fn start(&self, name: &'static str) -> Meter {
+        Meter {
+            name,
+            budget: *self,
+            started: Instant::now(),
+            steps: 0,
+        }
+    }
+}
+
+pub struct Meter {
+    name: &'static str,
+    budget: Budget,
+    started: Instant,
+    steps: u64,
+}
+
+impl Meter {
+    /// Counts an iteration of the loop, failing once the loop ran out of its budget.
+    pub fn step(&mut self) -> Result<()> {
+        self.steps += 1;
+
+        if let Some(steps) = self.budget.steps {
+            if self.steps > steps {
+                return Err(Error::OutOfBudget(self.name, format!("{steps} steps")));
+            }
+        }
+
+        if let Some(time) = self.budget.time {
+            if self.steps.is_multiple_of(CLOCK_EVERY) && self.started.elapsed() > time {
+                return Err(Error::OutOfBudget(self.name, format!("{time:?}")));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn steps_until_out(meter: &mut Meter, max: u64) -> Option<u64> {
+        (1..=max).find(|_| meter.step().is_err())
+    }
+
+    #[test]
+    fn unlimited_by_default() {
+        let budget = Budget::from_params(&Params::new()).unwrap();
+
+        assert!(budget.time.is_none() && budget.steps.is_none());
+        assert_eq!(steps_until_out(&mut budget.start("loop"), 10_000), None);
+    }
+
+    #[test]
+    fn out_of_steps() {
+        let params = Params::new().with("max_steps", 5);
+        let mut meter = Budget::from_params(&params).unwrap().start("loop");
+
+        assert_eq!(steps_until_out(&mut meter, 100), Some(6));
+        assert!(matches!(
+            meter.step(),
+            Err(Error::OutOfBudget("loop", limit)) if limit == "5 steps"
+        ));
+    }
+
+    #[test]
+    fn out_of_time() {
+        let params = Params::new().with("timeout", 0.001);
+        let budget = Budget::from_params(&params).unwrap();
+        assert_eq!(budget.time, Some(Duration::from_millis(1)));
+
+        let mut meter = budget.start("loop");
+        assert_eq!(steps_until_out(&mut meter, CLOCK_EVERY - 1), None);
+
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(steps_until_out(&mut meter, CLOCK_EVERY), Some(1));
+        assert_eq!(steps_until_out(&mut meter, CLOCK_EVERY), Some(CLOCK_EVERY));
+    }
+
+    #[test]
+    fn meters_start_afresh() {
+        let budget = Budget {
+            time: None,
+            steps: Some(3),
+        };
+
+        let mut first = budget.start("first");
+        assert_eq!(steps_until_out(&mut first, 10), Some(4));
+        assert_eq!(steps_until_out(&mut budget.start("second"), 10), Some(4));
+    }
+
+    #[test]
+    fn invalid_limits() {
+        for (name, value) in [("timeout", "-1"), ("timeout", "inf"), ("max_steps", "-3")] {
+            assert!(matches!(
+                Budget::from_params(&Params::new().with(name, value)),
+                Err(Error::InvalidParam(n, v)) if n == name && v == value
+            ));
+        }
+    }
+}
@@ -0,0 +1,293 @@
+use std::fmt::{self, Display};
+
+use super::Vec2;
+
+/// One of the 4 orthogonal directions on a grid, with y growing downwards. The directions are
+/// ordered clockwise from `Up`, which is also their index into a `[T; 4]`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Dir4 {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Dir4 {
+    pub const ALL: [Dir4; 4] = [Dir4::Up, Dir4::Right, Dir4::Down, Dir4::Left];
+
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn cw(self) -> Self {
+        Self::ALL[(self.index() + 1) % 4]
+    }
+
+    pub fn ccw(self) -> Self {
+        Self::ALL[(self.index() + 3) % 4]
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self.index() + 2) % 4]
+    }
+
+    /// The step to the neighbouring cell in this direction.
+    pub const fn delta(self) -> Vec2 {
+        match self {
+            Dir4::Up => Vec2::new(0, -1),
+            Dir4::Right => Vec2::new(1, 0),
+            Dir4::Down => Vec2::new(0, 1),
+            Dir4::Left => Vec2::new(-1, 0),
+        }
+    }
+
+    /// Direction of one of the arrows `^`, `>`, `v` and `<`.
+    pub fn from_arrow(c: char) -> Option<Self> {
+        match c {
+            '^' => Some(Dir4::Up),
+            '>' => Some(Dir4::Right),
+            'v' => Some(Dir4::Down),
+            '<' => Some(Dir4::Left),
+            _ => None,
+        }
+    }
+
+    /// Direction of one of the compass points `N`, `E`, `S` and `W`, north being up.
+    pub fn from_compass(c: char) -> Option<Self> {
+        match c {
+            'N' => Some(Dir4::Up),
+            'E' => Some(Dir4::Right),
+            'S' => Some(Dir4::Down),
+            'W' => Some(Dir4::Left),
+            _ => None,
+        }
+    }
+
+    pub fn arrow(self) -> char {
+        match self {
+            Dir4::Up => '^',
+            Dir4::Right => '>',
+            Dir4::Down => 'v',
+            Dir4::Left => '<',
+        }
+    }
+}
+
+/// Displays as the arrow `from_arrow` reads.
+impl Display for Dir4 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.arrow())
+    }
+}
+
+/// One of the 8 directions to the neighbours of a cell, diagonals included, with y growing
+/// downwards. The directions are ordered clockwise from `Up`, which is also their index into a
+/// `[T; 8]`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Dir8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Dir8 {
+    pub const ALL: [Dir8; 8] = [
+        Dir8::Up,
+        Dir8::UpRight,
+        Dir8::Right,
+        Dir8::DownRight,
+        Dir8::Down,
+        Dir8::DownLeft,
+        Dir8::Left,
+        Dir8::UpLeft,
+    ];
+
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    /// The next direction clockwise, an eighth of a turn.
+    pub fn cw(self) -> Self {
+        Self::ALL[(self.index() + 1) % 8]
+    }
+
+    /// The next direction counter-clockwise, an eighth of a turn.
+    pub fn ccw(self) -> Self {
+        Self::ALL[(self.index() + 7) % 8]
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self.index() + 4) % 8]
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self.index() % 2 == 1
+    }
+
+    /// The step to the neighbouring cell in this direction.
+    pub const fn delta(self) -> Vec2 {
+        match self {
+            Dir8::Up => Vec2::new(0, -1),
+            Dir8::UpRight => Vec2::new(1, -1),
+            Dir8::Right => Vec2::new(1, 0),
+            Dir8::DownRight => Vec2::new(1, 1),
+            Dir8::Down => Vec2::new(0, 1),
+            Dir8::DownLeft => Vec2::new(-1, 1),
+            Dir8::Left => Vec2::new(-1, 0),
+            Dir8::UpLeft => Vec2::new(-1, -1),
+        }
+    }
+
+    /// Direction of one of the compass points `N`, `NE`, `E`, `SE`, `S`, `SW`, `W` and `NW`,
+    /// north being up.
+    pub fn from_compass(s: &str) -> Option<Self> {
+        const POINTS: [&str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+
+        POINTS
+            .iter()
+            .position(|&point| point == s)
+            .map(|i| Self::ALL[i])
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(dir: Dir4) -> Self {
+        Self::ALL[dir.index() * 2]
+    }
+}
+
+impl<T> std::ops::Index<Dir4> for [T; 4] {
+    type Output = T;
+
+    fn index(&self, dir: Dir4) -> &Self::Output {
+        &self[dir.index()]
+    }
+}
+
+impl<T> std::ops::IndexMut<Dir4> for [T; 4] {
+    fn index_mut(&mut self, dir: Dir4) -> &mut Self::Output {
+        &mut self[dir.index()]
+    }
+}
+
+impl<T> std::ops::Index<Dir8> for [T; 8] {
+    type Output = T;
+
+    fn index(&self, dir: Dir8) -> &Self::Output {
+        &self[dir.index()]
+    }
+}
+
+impl<T> std::ops::IndexMut<Dir8> for [T; 8] {
+    fn index_mut(&mut self, dir: Dir8) -> &mut Self::Output {
+        &mut self[dir.index()]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::util::grid::{NEIGHBOURS_4, NEIGHBOURS_8};
+
+    #[test]
+    fn dir4_turns() {
+        assert_eq!(Dir4::Up.cw(), Dir4::Right);
+        assert_eq!(Dir4::Up.ccw(), Dir4::Left);
+        assert_eq!(Dir4::Left.opposite(), Dir4::Right);
+
+        for dir in Dir4::ALL {
+            assert_eq!(dir.cw().ccw(), dir);
+            assert_eq!(dir.ccw().cw(), dir);
+            assert_eq!(dir.cw().cw(), dir.opposite());
+            assert_eq!(dir.opposite().opposite(), dir);
+            assert_eq!(dir.opposite().delta(), dir.delta() * -1);
+        }
+    }
+
+    #[test]
+    fn dir8_turns() {
+        assert_eq!(Dir8::Up.cw(), Dir8::UpRight);
+        assert_eq!(Dir8::Up.ccw(), Dir8::UpLeft);
+        assert_eq!(Dir8::DownLeft.opposite(), Dir8::UpRight);
+
+        for dir in Dir8::ALL {
+            assert_eq!(dir.cw().ccw(), dir);
+            assert_eq!(dir.ccw().cw(), dir);
+            assert_eq!(dir.cw().cw().cw().cw(), dir.opposite());
+            assert_eq!(dir.opposite().delta(), dir.delta() * -1);
+            assert_eq!(dir.is_diagonal(), dir.delta().manhattan_len() == 2);
+        }
+    }
+
+    #[test]
+    fn deltas_in_neighbour_order() {
+        assert_eq!(Dir4::ALL.map(Dir4::delta), NEIGHBOURS_4);
+        assert_eq!(Dir8::ALL.map(Dir8::delta), NEIGHBOURS_8);
+        assert_eq!(Dir4::Up.delta(), Vec2::new(0, -1));
+        assert_eq!(Dir8::DownRight.delta(), Vec2::new(1, 1));
+
+        for (i, dir) in Dir8::ALL.into_iter().enumerate() {
+            assert_eq!(dir.index(), i);
+        }
+    }
+
+    #[test]
+    fn arrows() {
+        for (arrow, dir) in "^>v<".chars().zip(Dir4::ALL) {
+            assert_eq!(Dir4::from_arrow(arrow), Some(dir));
+            assert_eq!(dir.arrow(), arrow);
+            assert_eq!(dir.to_string(), arrow.to_string());
+        }
+
+        assert_eq!(Dir4::from_arrow('V'), None);
+        assert_eq!(Dir4::from_arrow('N'), None);
+    }
+
+    #[test]
+    fn compass_points() {
+        for (point, dir) in "NESW".chars().zip(Dir4::ALL) {
+            assert_eq!(Dir4::from_compass(point), Some(dir));
+        }
+
+        let points = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+        for (point, dir) in points.into_iter().zip(Dir8::ALL) {
+            assert_eq!(Dir8::from_compass(point), Some(dir));
+        }
+
+        assert_eq!(Dir4::from_compass('^'), None);
+        assert_eq!(Dir8::from_compass("EN"), None);
+        assert_eq!(Dir8::from_compass(""), None);
+    }
+
+    #[test]
+    fn dir4_as_dir8() {
+        for dir in Dir4::ALL {
+            let dir8 = Dir8::from(dir);
+
+            assert_eq!(dir8.delta(), dir.delta());
+            assert!(!dir8.is_diagonal());
+            assert_eq!(Dir8::from(dir.cw()), dir8.cw().cw());
+        }
+    }
+
+    #[test]
+    fn indexed_arrays() {
+        let mut counts = [0; 4];
+        counts[Dir4::Down] += 1;
+        counts[Dir4::Left] += 2;
+        assert_eq!(counts, [0, 0, 1, 2]);
+        assert_eq!(counts[Dir4::Left], 2);
+
+        let mut names = [""; 8];
+        names[Dir8::UpLeft] = "nw";
+        names[Dir8::Right] = "e";
+        assert_eq!(names[Dir8::UpLeft], "nw");
+        assert_eq!(names[7], "nw");
+        assert_eq!(names[2], "e");
+    }
+}
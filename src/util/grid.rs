@@ -1,27 +1,122 @@
-use super::Vec2;
+use std::collections::HashMap;
 
-#[derive(Debug)]
+use super::parse::{self, Line};
+use super::{Dir4, Dir8, Vec2};
+use crate::error::ParseError;
+use crate::input::Input;
+
+/// Offsets of the 4 orthogonal neighbours of a cell, clockwise from the one above.
+pub const NEIGHBOURS_4: [Vec2; 4] = [
+    Dir4::Up.delta(),
+    Dir4::Right.delta(),
+    Dir4::Down.delta(),
+    Dir4::Left.delta(),
+];
+
+/// Offsets of all 8 neighbours of a cell, clockwise from the one above.
+pub const NEIGHBOURS_8: [Vec2; 8] = [
+    Dir8::Up.delta(),
+    Dir8::UpRight.delta(),
+    Dir8::Right.delta(),
+    Dir8::DownRight.delta(),
+    Dir8::Down.delta(),
+    Dir8::DownLeft.delta(),
+    Dir8::Left.delta(),
+    Dir8::UpLeft.delta(),
+];
+
+/// A rectangular grid of cells, stored row by row in a single `Vec`.
+#[derive(Clone, Debug, PartialEq)]
 pub struct Grid<T> {
-    data: Vec<Vec<T>>,
+    data: Vec<T>,
     dims: Vec2,
 }
 
 impl<T> Grid<T> {
-    pub fn new(data: Vec<Vec<T>>) -> Self {
-        let height = data.len();
-        let width = if height > 0 { data[0].len() } else { 0 };
+    pub fn new(rows: Vec<Vec<T>>) -> Self {
+        let height = rows.len();
+        let width = if height > 0 { rows[0].len() } else { 0 };
 
-        debug_assert!(
-            data.iter().all(|row| row.len() == width),
+        assert!(
+            rows.iter().all(|row| row.len() == width),
             "grid rows have different lengths"
         );
 
+        Self {
+            data: rows.into_iter().flatten().collect(),
+            dims: Vec2::cell(width, height),
+        }
+    }
+
+    /// Grid of `width` columns over the cells in `data`, row by row.
+    pub fn from_vec(width: usize, data: Vec<T>) -> Self {
+        let height = data.len().checked_div(width).unwrap_or(0);
+
+        assert_eq!(
+            width * height,
+            data.len(),
+            "grid cells don't fill whole rows"
+        );
+
         Self {
             data,
-            dims: (width, height).into(),
+            dims: Vec2::cell(width, height),
         }
     }
 
+    /// Reads a grid from the lines of `input` up to the next blank line or the end of the input,
+    /// mapping each character to a cell with `cell`, which returns what it expected instead for
+    /// characters it doesn't know.
+    ///
+    /// The positions of the `markers` characters, e.g. the start and end of a maze, are returned
+    /// along with the grid. Marker characters are mapped to cells like any other.
+    pub fn parse(
+        input: &mut Input,
+        markers: &[char],
+        mut cell: impl FnMut(char) -> Result<T, &'static str>,
+    ) -> Result<(Self, Markers), ParseError> {
+        let lines = parse::read_section(input)
+            .ok_or_else(|| ParseError::eof(input.line_number() + 1, "a grid"))?;
+
+        let width = lines[0].trim_end().chars().count();
+        let mut data = Vec::with_capacity(width * lines.len());
+        let mut found = Markers {
+            positions: Vec::new(),
+            lines: HashMap::new(),
+            first_line: Line::new(lines[0].line_no, &lines[0]),
+        };
+
+        for (y, line) in lines.into_iter().enumerate() {
+            let row = line.trim_end();
+            let row_width = row.chars().count();
+
+            if row_width != width {
+                let rest = row.char_indices().nth(width).map_or("", |(i, _)| &row[i..]);
+                return Err(line.error(rest, format!("a row of {width} cells")));
+            }
+
+            for (x, (i, c)) in row.char_indices().enumerate() {
+                let value =
+                    cell(c).map_err(|expected| line.error(&row[i..i + c.len_utf8()], expected))?;
+                data.push(value);
+
+                if markers.contains(&c) {
+                    found.positions.push((c, Vec2::cell(x, y)));
+                }
+            }
+
+            if found
+                .positions
+                .last()
+                .is_some_and(|(_, pos)| pos.y == y as i64)
+            {
+                found.lines.insert(y as i64, line);
+            }
+        }
+
+        Ok((Self::from_vec(width, data), found))
+    }
+
     pub fn with_size<S>(width: S, height: S, default: T) -> Self
     where
         T: Clone,
@@ -30,10 +125,9 @@ impl<T> Grid<T> {
         let width = width.into();
         let height = height.into();
 
-        let data = vec![vec![default; width]; height];
         Self {
-            data,
-            dims: Vec2::from((width, height)),
+            data: vec![default; width * height],
+            dims: Vec2::cell(width, height),
         }
     }
 
@@ -49,8 +143,118 @@ impl<T> Grid<T> {
         self.dims.y as usize
     }
 
-    pub fn iter(&self) -> GridIter<T> {
-        GridIter::new(self)
+    pub fn contains(&self, pos: Vec2) -> bool {
+        pos.inside(&self.dims)
+    }
+
+    pub fn get(&self, pos: Vec2) -> Option<&T> {
+        self.contains(pos).then(|| &self[pos])
+    }
+
+    pub fn get_mut(&mut self, pos: Vec2) -> Option<&mut T> {
+        if self.contains(pos) {
+            Some(&mut self[pos])
+        } else {
+            None
+        }
+    }
+
+    /// The cells at the `offsets` from `pos` that are inside the grid.
+    pub fn around<'a>(
+        &'a self,
+        pos: Vec2,
+        offsets: &'a [Vec2],
+    ) -> impl Iterator<Item = (Vec2, &'a T)> + 'a {
+        offsets
+            .iter()
+            .map(move |&offset| pos + offset)
+            .filter_map(|pos| self.get(pos).map(|cell| (pos, cell)))
+    }
+
+    /// The orthogonal neighbours of `pos` inside the grid.
+    pub fn neighbours4(&self, pos: Vec2) -> impl Iterator<Item = (Vec2, &T)> {
+        self.around(pos, &NEIGHBOURS_4)
+    }
+
+    /// The orthogonal and diagonal neighbours of `pos` inside the grid.
+    pub fn neighbours8(&self, pos: Vec2) -> impl Iterator<Item = (Vec2, &T)> {
+        self.around(pos, &NEIGHBOURS_8)
+    }
+
+    /// The cells from `from` on, stepping by `step` until leaving the grid. `from` itself is the
+    /// first one if it's inside.
+    pub fn ray(&self, from: Vec2, step: Vec2) -> impl Iterator<Item = (Vec2, &T)> {
+        std::iter::successors(Some(from), move |&pos| Some(pos + step))
+            .map_while(|pos| self.get(pos).map(|cell| (pos, cell)))
+    }
+
+    /// All the lines of cells across the grid in the direction of `step`, each running from the
+    /// edge of the grid to the opposite one. E.g. the rows for `(1, 0)`, the columns for `(0, 1)`
+    /// and the diagonals for `(1, 1)` and `(-1, 1)`.
+    pub fn lines(&self, step: Vec2) -> impl Iterator<Item = impl Iterator<Item = (Vec2, &T)>> {
+        self.iter()
+            .map(|(pos, _)| pos)
+            .filter(move |&pos| !self.contains(pos - step))
+            .map(move |start| self.ray(start, step))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        let width = self.width();
+        &self.data[y * width..(y + 1) * width]
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        let width = self.width();
+        &mut self.data[y * width..(y + 1) * width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height()).map(|y| self.row(y))
+    }
+
+    /// The cells with their positions, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Vec2, &T)> {
+        let width = self.width().max(1);
+
+        self.data
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| (Vec2::cell(i % width, i / width), cell))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Vec2, &mut T)> {
+        let width = self.width().max(1);
+
+        self.data
+            .iter_mut()
+            .enumerate()
+            .map(move |(i, cell)| (Vec2::cell(i % width, i / width), cell))
+    }
+
+    /// Grid of the same size with `f` applied to each cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            data: self.data.iter().map(f).collect(),
+            dims: self.dims,
+        }
+    }
+
+    pub fn fill(&mut self, value: T)
+    where
+        T: Clone,
+    {
+        self.data.fill(value);
+    }
+
+    fn offset(&self, x: usize, y: usize) -> usize {
+        assert!(
+            x < self.width() && y < self.height(),
+            "position ({x}, {y}) outside of the {}x{} grid",
+            self.width(),
+            self.height()
+        );
+
+        y * self.width() + x
     }
 }
 
@@ -58,13 +262,14 @@ impl<T> std::ops::Index<Vec2> for Grid<T> {
     type Output = T;
 
     fn index(&self, pos: Vec2) -> &Self::Output {
-        &self.data[pos.y as usize][pos.x as usize]
+        &self.data[self.offset(pos.x as usize, pos.y as usize)]
     }
 }
 
 impl<T> std::ops::IndexMut<Vec2> for Grid<T> {
     fn index_mut(&mut self, pos: Vec2) -> &mut Self::Output {
-        &mut self.data[pos.y as usize][pos.x as usize]
+        let offset = self.offset(pos.x as usize, pos.y as usize);
+        &mut self.data[offset]
     }
 }
 
@@ -75,10 +280,7 @@ where
     type Output = T;
 
     fn index(&self, pos: (I, I)) -> &Self::Output {
-        let x = pos.0.into();
-        let y = pos.1.into();
-
-        &self.data[y][x]
+        &self.data[self.offset(pos.0.into(), pos.1.into())]
     }
 }
 
@@ -87,43 +289,234 @@ where
     I: Into<usize>,
 {
     fn index_mut(&mut self, pos: (I, I)) -> &mut Self::Output {
-        let x = pos.0.into();
-        let y = pos.1.into();
-
-        &mut self.data[y][x]
+        let offset = self.offset(pos.0.into(), pos.1.into());
+        &mut self.data[offset]
     }
 }
 
-pub struct GridIter<'a, T> {
-    grid: &'a Grid<T>,
-    pos: Vec2,
+/// Positions of the marker characters found by `Grid::parse`.
+pub struct Markers {
+    positions: Vec<(char, Vec2)>,
+    /// Lines the markers are on, by row, for errors about them.
+    lines: HashMap<i64, Line>,
+    /// First line of the grid, which errors about missing markers point at.
+    first_line: Line,
 }
 
-impl<'a, T> GridIter<'a, T> {
-    pub fn new(grid: &'a Grid<T>) -> Self {
-        Self {
-            grid,
-            pos: Vec2::default(),
+impl Markers {
+    /// All the positions of `marker`, row by row.
+    pub fn all(&self, marker: char) -> impl Iterator<Item = Vec2> + '_ {
+        self.positions
+            .iter()
+            .filter(move |&&(c, _)| c == marker)
+            .map(|&(_, pos)| pos)
+    }
+
+    /// The position of `marker`, which has to be in the grid exactly once.
+    pub fn single(&self, marker: char) -> Result<Vec2, ParseError> {
+        let mut positions = self.all(marker);
+
+        let pos = positions.next().ok_or_else(|| ParseError {
+            text: String::new(),
+            ..self
+                .first_line
+                .line_error(format!("a marker {marker} in the grid"))
+        })?;
+
+        match positions.next() {
+            None => Ok(pos),
+            Some(other) => {
+                let line = &self.lines[&other.y];
+                let (i, _) = line
+                    .char_indices()
+                    .nth(other.x as usize)
+                    .unwrap_or_default();
+                Err(line.error(
+                    &line[i..i + marker.len_utf8()],
+                    format!("only one marker {marker} in the grid"),
+                ))
+            }
         }
     }
 }
 
-impl<'a, T> Iterator for GridIter<'a, T> {
-    type Item = (Vec2, &'a T);
+#[cfg(test)]
+mod test {
+    use super::*;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.pos.y >= self.grid.dims.y {
-            return None;
-        }
+    fn parse(input: &str) -> Result<(Grid<char>, Markers), ParseError> {
+        Grid::parse(&mut Input::from_str(input), &['S', 'E'], |c| match c {
+            '.' | '#' | 'S' | 'E' => Ok(c),
+            _ => Err("a tile"),
+        })
+    }
+
+    #[test]
+    fn parse_grid_and_markers() {
+        let (grid, markers) = parse("#S.\n.E#\n\nrest\n").unwrap();
+
+        assert_eq!(grid.dims(), Vec2::new(3, 2));
+        assert_eq!(grid[(2_usize, 1_usize)], '#');
+        assert_eq!(markers.single('S').unwrap(), Vec2::new(1, 0));
+        assert_eq!(markers.single('E').unwrap(), Vec2::new(1, 1));
+        assert_eq!(markers.all('E').collect::<Vec<_>>(), vec![Vec2::new(1, 1)]);
+    }
+
+    #[test]
+    fn ragged_row() {
+        let error = parse("#S.\n.E#.\n").err().unwrap();
+        assert_eq!((error.line, error.column), (2, 4));
+        assert_eq!(error.text, ".");
+        assert_eq!(error.expected, "a row of 3 cells");
+
+        let error = parse("#S.\n.E\n").err().unwrap();
+        assert_eq!((error.line, error.column), (2, 3));
+        assert_eq!(error.text, "");
+    }
+
+    #[test]
+    fn unknown_character() {
+        let error = parse("#S.\n.Ex\n").err().unwrap();
+
+        assert_eq!((error.line, error.column), (2, 3));
+        assert_eq!(error.text, "x");
+        assert_eq!(error.expected, "a tile");
+    }
+
+    #[test]
+    fn duplicate_marker() {
+        let (_, markers) = parse("#S.\n.E#\nS..\n").unwrap();
+        let error = markers.single('S').unwrap_err();
+
+        assert_eq!((error.line, error.column), (3, 1));
+        assert_eq!(error.text, "S");
+        assert_eq!(error.expected, "only one marker S in the grid");
+    }
+
+    #[test]
+    fn missing_marker() {
+        let (_, markers) = parse("\n#S.\n...\n\nrest\n").unwrap();
+        let error = markers.single('E').unwrap_err();
+
+        // points at the grid, not at where the input or the section ends
+        assert_eq!((error.line, error.column), (2, 1));
+        assert_eq!(error.source, "#S.");
+        assert_eq!(error.text, "");
+        assert_eq!(error.expected, "a marker E in the grid");
+    }
+
+    #[test]
+    fn missing_grid() {
+        let error = parse("\n\n").err().unwrap();
+
+        assert_eq!(error.expected, "a grid");
+        assert_eq!(error.text, "");
+    }
 
-        let pos = self.pos;
-        self.pos.x += 1;
+    fn numbered(width: usize, height: usize) -> Grid<usize> {
+        Grid::from_vec(width, (0..width * height).collect())
+    }
+
+    fn cells<'a>(walk: impl Iterator<Item = (Vec2, &'a usize)>) -> Vec<usize> {
+        walk.map(|(_, &cell)| cell).collect()
+    }
+
+    #[test]
+    fn neighbours_inside() {
+        let grid = numbered(3, 3);
+        let centre = Vec2::new(1, 1);
 
-        if self.pos.x >= self.grid.dims.x {
-            self.pos.x = 0;
-            self.pos.y += 1;
+        assert_eq!(cells(grid.neighbours4(centre)), vec![1, 5, 7, 3]);
+        assert_eq!(
+            cells(grid.neighbours8(centre)),
+            vec![1, 2, 5, 8, 7, 6, 3, 0]
+        );
+
+        for (pos, &cell) in grid.neighbours8(centre) {
+            assert_eq!(grid[pos], cell);
         }
+    }
+
+    #[test]
+    fn neighbours_at_edges_and_corners() {
+        let grid = numbered(3, 3);
 
-        Some((pos, &self.grid[pos]))
+        assert_eq!(cells(grid.neighbours4(Vec2::new(0, 0))), vec![1, 3]);
+        assert_eq!(cells(grid.neighbours8(Vec2::new(0, 0))), vec![1, 4, 3]);
+        assert_eq!(cells(grid.neighbours4(Vec2::new(2, 2))), vec![5, 7]);
+        assert_eq!(cells(grid.neighbours8(Vec2::new(2, 2))), vec![5, 7, 4]);
+        assert_eq!(cells(grid.neighbours4(Vec2::new(1, 0))), vec![2, 4, 0]);
+        assert_eq!(
+            cells(grid.neighbours8(Vec2::new(1, 0))),
+            vec![2, 5, 4, 3, 0]
+        );
+
+        // the neighbours of a position just outside are the cells next to it
+        assert_eq!(cells(grid.neighbours4(Vec2::new(-1, 0))), vec![0]);
+        assert_eq!(cells(grid.neighbours8(Vec2::new(3, 3))), vec![8]);
+    }
+
+    #[test]
+    fn ray_stops_at_the_border() {
+        let grid = numbered(4, 3);
+
+        assert_eq!(
+            cells(grid.ray(Vec2::new(1, 0), Vec2::new(1, 0))),
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            cells(grid.ray(Vec2::new(0, 0), Vec2::new(1, 1))),
+            vec![0, 5, 10]
+        );
+        assert_eq!(
+            cells(grid.ray(Vec2::new(3, 2), Vec2::new(-2, -1))),
+            vec![11, 5]
+        );
+        assert_eq!(cells(grid.ray(Vec2::new(0, 2), Vec2::new(0, 1))), vec![8]);
+
+        // a ray starting outside is empty even if it would enter the grid
+        assert_eq!(cells(grid.ray(Vec2::new(-1, 0), Vec2::new(1, 0))), vec![]);
+
+        let ray = grid
+            .ray(Vec2::new(0, 1), Vec2::new(1, 0))
+            .collect::<Vec<_>>();
+        assert_eq!(ray[2], (Vec2::new(2, 1), &6));
+    }
+
+    #[test]
+    fn lines_across_the_grid() {
+        let grid = numbered(3, 2);
+        let lines = |step| grid.lines(step).map(cells).collect::<Vec<_>>();
+
+        assert_eq!(lines(Vec2::new(1, 0)), vec![vec![0, 1, 2], vec![3, 4, 5]]);
+        assert_eq!(
+            lines(Vec2::new(0, 1)),
+            vec![vec![0, 3], vec![1, 4], vec![2, 5]]
+        );
+        assert_eq!(lines(Vec2::new(-1, 0)), vec![vec![2, 1, 0], vec![5, 4, 3]]);
+    }
+
+    #[test]
+    fn diagonal_lines_start_at_the_edges() {
+        let grid = numbered(3, 2);
+        let lines = |step| grid.lines(step).map(cells).collect::<Vec<_>>();
+
+        // 0 1 2
+        // 3 4 5
+        assert_eq!(
+            lines(Vec2::new(1, 1)),
+            vec![vec![0, 4], vec![1, 5], vec![2], vec![3]]
+        );
+        assert_eq!(
+            lines(Vec2::new(-1, 1)),
+            vec![vec![0], vec![1, 3], vec![2, 4], vec![5]]
+        );
+
+        // every cell is on exactly one line of each direction
+        for step in NEIGHBOURS_8 {
+            let mut all = lines(step).concat();
+            all.sort();
+            assert_eq!(all, (0..6).collect::<Vec<_>>());
+        }
     }
 }
@@ -1,10 +1,21 @@
+pub mod budget;
 pub mod grid;
 pub mod math;
+pub mod parallel;
+pub mod parse;
+pub mod record;
+pub mod render;
 
 pub use args::*;
 pub use dims::*;
+pub use dir::*;
+pub use table::*;
 pub use vec2::*;
+pub use vec3::*;
 
 mod args;
 mod dims;
+mod dir;
+mod table;
 mod vec2;
+mod vec3;
@@ -0,0 +1,136 @@
+//! Data parallelism for the days that solve many independent subproblems, on scoped threads.
+//!
+//! The number of threads is set once for the whole run with `--threads`, it defaults to the
+//! available parallelism of the machine.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Threads used by `map`, 0 until set, meaning all available.
+static THREADS: AtomicUsize = AtomicUsize::new(0);
+
+pub fn set_threads(threads: usize) {
+    THREADS.store(threads, Ordering::Relaxed);
+}
+
+/// Threads used by `map`.
+pub fn threads() -> usize {
+    match THREADS.load(Ordering::Relaxed) {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        threads => threads,
+    }
+}
+
+/// Maps `f` over `items` split into a contiguous chunk per thread, the results keep the order
+/// of the items.
+pub fn map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    map_on(threads(), items, f)
+}
+
+/// `map` on at most `threads` threads.
+fn map_on<T, R, F>(threads: usize, items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let threads = threads.min(items.len());
+    if threads <= 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let chunk_size = items.len().div_ceil(threads);
+    let f = &f;
+
+    thread::scope(|scope| {
+        let handles = items
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || chunk.iter().map(f).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use std::collections::HashSet;
+    use std::sync::Mutex;
+
+    /// The thread count is global, so the tests that set it take turns.
+    static SETTING: Mutex<()> = Mutex::new(());
+
+    /// Runs `f` with the thread count set to `threads`, resetting it afterwards.
+    pub(crate) fn with_threads<R>(threads: usize, f: impl FnOnce() -> R) -> R {
+        struct Reset;
+
+        impl Drop for Reset {
+            fn drop(&mut self) {
+                set_threads(0);
+            }
+        }
+
+        let _turn = SETTING.lock().unwrap_or_else(|e| e.into_inner());
+        let _reset = Reset;
+        set_threads(threads);
+
+        f()
+    }
+
+    #[test]
+    fn keeps_order_across_chunks() {
+        // 4 threads split the 103 items into chunks of 26, the last one shorter
+        let items = (0..103).collect::<Vec<_>>();
+
+        assert_eq!(
+            map_on(4, &items, |n| n * 2),
+            (0..103).map(|n| n * 2).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn empty_items() {
+        assert_eq!(map_on(4, &[] as &[u8], |&n| n), vec![]);
+    }
+
+    #[test]
+    fn more_threads_than_items() {
+        let workers = Mutex::new(HashSet::new());
+        let squares = map_on(16, &[1, 2, 3], |&n| {
+            workers.lock().unwrap().insert(thread::current().id());
+            n * n
+        });
+
+        assert_eq!(squares, vec![1, 4, 9]);
+        assert_eq!(workers.into_inner().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn same_results_on_any_thread_count() {
+        let items = (0..50_u64).collect::<Vec<_>>();
+        let serial = map_on(1, &items, |n| n.pow(3) % 7);
+
+        for threads in 2..=8 {
+            assert_eq!(map_on(threads, &items, |n| n.pow(3) % 7), serial);
+        }
+    }
+
+    #[test]
+    fn set_thread_count() {
+        assert_eq!(with_threads(3, threads), 3);
+        assert!(with_threads(0, threads) >= 1);
+    }
+}
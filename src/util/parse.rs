@@ -0,0 +1,287 @@
+//! Parsers for the line formats shared by the puzzle inputs.
+//!
+//! Input is read as `Line`s, which remember their line number. Parsers take a part of the line
+//! (a subslice of it) and on failure return a `ParseError` pointing at exactly that part.
+
+use std::ops::Deref;
+use std::str::FromStr;
+
+use crate::error::ParseError;
+use crate::input::Input;
+use crate::util::Vec2;
+
+/// A line of input without its line break.
+pub struct Line {
+    pub line_no: usize,
+    pub text: String,
+}
+
+impl Deref for Line {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.text
+    }
+}
+
+impl Line {
+    pub fn new(line_no: usize, text: &str) -> Self {
+        Self {
+            line_no,
+            text: text.trim_end_matches(['\r', '\n']).to_string(),
+        }
+    }
+
+    pub fn is_blank(&self) -> bool {
+        self.text.trim().is_empty()
+    }
+
+    /// Error for `part` of this line.
+    pub fn error(&self, part: &str, expected: impl Into<String>) -> ParseError {
+        ParseError::at(self.line_no, &self.text, part, expected)
+    }
+
+    /// Error for the whole line.
+    pub fn line_error(&self, expected: impl Into<String>) -> ParseError {
+        ParseError::new(self.line_no, &self.text, expected)
+    }
+
+    pub fn parse<T: FromStr>(&self, part: &str, expected: &str) -> Result<T, ParseError> {
+        part.trim().parse().map_err(|_| self.error(part, expected))
+    }
+
+    pub fn unsigned<T: FromStr>(&self, part: &str) -> Result<T, ParseError> {
+        self.parse(part, "a number")
+    }
+
+    pub fn signed(&self, part: &str) -> Result<i64, ParseError> {
+        self.parse(part, "an integer")
+    }
+
+    /// Strips `prefix` from `part`.
+    pub fn strip_prefix<'a>(&self, part: &'a str, prefix: &str) -> Result<&'a str, ParseError> {
+        part.strip_prefix(prefix)
+            .ok_or_else(|| self.error(part, format!("{prefix}<value>")))
+    }
+
+    /// Splits the line in two at the first `separator`.
+    pub fn split_once(&self, separator: &str, expected: &str) -> Result<(&str, &str), ParseError> {
+        self.text
+            .split_once(separator)
+            .ok_or_else(|| self.line_error(expected))
+    }
+
+    /// Splits a `key: value` line.
+    pub fn key_value(&self) -> Result<(&str, &str), ParseError> {
+        self.split_once(": ", "key: value")
+    }
+
+    /// The value of a `key: value` line with the given `key`, e.g. `Register A: 729`.
+    pub fn value(&self, key: &str) -> Result<&str, ParseError> {
+        match self.key_value() {
+            Ok((k, value)) if k == key => Ok(value),
+            _ => Err(self.line_error(format!("{key}: <value>"))),
+        }
+    }
+
+    /// Parses a pair of integers separated by a comma into a `Vec2`.
+    ///
+    /// Each of them may be labeled, so `0,4`, `p=0,4`, `X+94, Y+34` and `X=8400, Y=5400` are all
+    /// accepted.
+    pub fn vec2(&self, part: &str) -> Result<Vec2, ParseError> {
+        let expected = "a vector like x,y";
+
+        let (x, y) = part
+            .split_once(',')
+            .ok_or_else(|| self.error(part, expected))?;
+
+        let component = |s: &str| {
+            let value = s
+                .trim_start()
+                .trim_start_matches(|c: char| c.is_ascii_alphabetic())
+                .trim_start_matches(['=', '+']);
+            self.signed(value)
+        };
+
+        Ok(Vec2::new(component(x)?, component(y)?))
+    }
+
+    /// All the integers on the line, in order. A `-` right before digits is taken as a sign.
+    pub fn ints(&self) -> Result<Vec<i64>, ParseError> {
+        let bytes = self.text.as_bytes();
+        let mut ints = Vec::new();
+        let mut i = 0;
+
+        while i < bytes.len() {
+            let negative = bytes[i] == b'-' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit);
+            if !negative && !bytes[i].is_ascii_digit() {
+                i += 1;
+                continue;
+            }
+
+            let start = i;
+            i += 1;
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+
+            ints.push(self.signed(&self.text[start..i])?);
+        }
+
+        Ok(ints)
+    }
+
+    /// Exactly `N` integers on the line, see `ints`.
+    pub fn ints_n<const N: usize>(&self, expected: &str) -> Result<[i64; N], ParseError> {
+        self.ints()?
+            .try_into()
+            .map_err(|_| self.line_error(expected))
+    }
+}
+
+/// Reads the next line, `None` at the end of the input.
+pub fn read_line(input: &mut Input) -> Option<Line> {
+    let text = input.read_line()?;
+    Some(Line::new(input.line_number(), &text))
+}
+
+/// Reads the next line, which has to be there.
+pub fn expect_line(input: &mut Input, expected: &str) -> Result<Line, ParseError> {
+    read_line(input).ok_or_else(|| ParseError::eof(input.line_number() + 1, expected))
+}
+
+/// The remaining lines of the input.
+pub fn lines(input: &mut Input) -> impl Iterator<Item = Line> + '_ {
+    std::iter::from_fn(|| read_line(input))
+}
+
+/// Reads the lines up to the next blank line or the end of the input, skipping blank lines
+/// before them. `None` once there are no lines left.
+pub fn read_section(input: &mut Input) -> Option<Vec<Line>> {
+    let mut section = Vec::new();
+
+    for line in lines(input) {
+        if !line.is_blank() {
+            section.push(line);
+        } else if !section.is_empty() {
+            break;
+        }
+    }
+
+    (!section.is_empty()).then_some(section)
+}
+
+/// The remaining blank-line separated sections of the input.
+pub fn sections(input: &mut Input) -> impl Iterator<Item = Vec<Line>> + '_ {
+    std::iter::from_fn(|| read_section(input))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn errors_point_at_the_part() {
+        let line = Line::new(3, "p=0,4 v=3,-3\n");
+        let e = line.error(&line[6..], "a velocity");
+
+        assert_eq!((e.line, e.column), (3, 7));
+        assert_eq!(e.text, "v=3,-3");
+        assert_eq!(e.source, "p=0,4 v=3,-3");
+        assert_eq!(e.expected, "a velocity");
+
+        let e = line.line_error("a robot");
+        assert_eq!((e.line, e.column), (3, 1));
+        assert_eq!(e.text, "p=0,4 v=3,-3");
+    }
+
+    #[test]
+    fn labeled_vectors() {
+        let line = Line::new(1, "Button A: X+94, Y+34");
+        let (_, vec) = line.key_value().unwrap();
+        assert_eq!(line.vec2(vec).unwrap(), Vec2::new(94, 34));
+
+        let line = Line::new(1, "p=0,4 v=-3,3");
+        let (p, v) = line.split_once(" ", "p v").unwrap();
+        assert_eq!(
+            line.vec2(line.strip_prefix(p, "p=").unwrap()).unwrap(),
+            Vec2::new(0, 4)
+        );
+        assert_eq!(line.vec2(v).unwrap(), Vec2::new(-3, 3));
+
+        let line = Line::new(1, "Prize: X=8400, Y=5400");
+        assert_eq!(
+            line.vec2(line.value("Prize").unwrap()).unwrap(),
+            (8400, 5400)
+        );
+
+        let line = Line::new(1, "12;34");
+        assert_eq!(line.vec2(&line).unwrap_err().expected, "a vector like x,y");
+
+        let line = Line::new(1, "12, ?3");
+        let e = line.vec2(&line).unwrap_err();
+        assert_eq!((e.column, e.text.as_str()), (5, "?3"));
+    }
+
+    #[test]
+    fn signed_ints() {
+        let line = Line::new(1, "p=-12,4 v=3,-3 x-1 --5 7-");
+
+        assert_eq!(line.ints().unwrap(), vec![-12, 4, 3, -3, -1, -5, 7]);
+        assert_eq!(Line::new(1, "no numbers").ints().unwrap(), vec![]);
+    }
+
+    #[test]
+    fn ints_n_count() {
+        let line = Line::new(2, "1 2 3");
+
+        assert_eq!(line.ints_n::<3>("three numbers").unwrap(), [1, 2, 3]);
+
+        let e = line.ints_n::<2>("two numbers").unwrap_err();
+        assert_eq!((e.line, e.expected.as_str()), (2, "two numbers"));
+    }
+
+    #[test]
+    fn values_by_key() {
+        let line = Line::new(1, "Register A: 729");
+
+        assert_eq!(line.value("Register A").unwrap(), "729");
+
+        let e = line.value("Register B").unwrap_err();
+        assert_eq!(e.expected, "Register B: <value>");
+        assert_eq!(e.text, "Register A: 729");
+
+        let e = Line::new(1, "Register A 729")
+            .value("Register A")
+            .unwrap_err();
+        assert_eq!(e.expected, "Register A: <value>");
+    }
+
+    #[test]
+    fn sections_between_blank_lines() {
+        let mut input = Input::from_str("\n\na\nb\n\n\n  \nc\n\n\n");
+        let sections: Vec<Vec<(usize, String)>> = sections(&mut input)
+            .map(|s| s.into_iter().map(|l| (l.line_no, l.text)).collect())
+            .collect();
+
+        assert_eq!(
+            sections,
+            vec![
+                vec![(3, "a".to_string()), (4, "b".to_string())],
+                vec![(8, "c".to_string())],
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_line() {
+        let mut input = Input::from_str("one\n");
+
+        assert!(matches!(expect_line(&mut input, "a line"), Ok(line) if line.text == "one"));
+
+        let Err(e) = expect_line(&mut input, "a line") else {
+            panic!("read past the end of the input");
+        };
+        assert_eq!((e.line, e.text.as_str()), (2, ""));
+    }
+}
@@ -0,0 +1,433 @@
+//! Recording of animations, frame by frame, for simulations that are best reviewed in motion.
+//!
+//! A day creates a `Recorder` with `from_env`, which only returns one if `AOC_RENDER_DIR` is set,
+//! pushes a frame for each step it wants to show and saves the recording when it's done. The
+//! recording is saved both as an asciicast (`<name>.cast`, play it with `asciinema play`) and as
+//! an animated GIF (`<name>.gif`).
+//!
+//! Long simulations are kept manageable with `AOC_FRAME_EVERY`, which keeps only every n-th
+//! frame, and `AOC_MAX_FRAMES`, which caps the number of frames kept. Only the latest frames are
+//! kept when a recording runs over the cap, so it always ends with the final state.
+
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+use log::{info, warn};
+
+use super::render::{Glyph, Renderer, Rgb, IMAGE_DIR_VAR};
+use crate::error::Result;
+
+/// Environment variable with the number of steps per recorded frame.
+pub const FRAME_EVERY_VAR: &str = "AOC_FRAME_EVERY";
+
+/// Environment variable with the maximum number of frames kept.
+pub const MAX_FRAMES_VAR: &str = "AOC_MAX_FRAMES";
+
+const DEFAULT_MAX_FRAMES: usize = 300;
+
+/// Side of a grid cell in the animated image, in pixels. Smaller than in still images, as
+/// animations have a lot of frames.
+const CELL_SIZE: usize = 2;
+
+/// Playback speed of the recordings, in frames per second.
+const FPS: usize = 10;
+
+type Frame = Vec<Vec<Glyph>>;
+
+pub struct Recorder {
+    target: &'static str,
+    name: String,
+    dir: PathBuf,
+    every: usize,
+    max_frames: usize,
+    ticks: usize,
+    dropped: usize,
+    frames: VecDeque<Frame>,
+}
+
+impl Recorder {
+    /// Recorder that keeps every `every`-th frame, up to the latest `max_frames`, and saves them
+    /// as `<name>.cast` and `<name>.gif` to `dir`. Problems are logged under `target`.
+    pub fn new(
+        target: &'static str,
+        name: &str,
+        dir: impl Into<PathBuf>,
+        every: usize,
+        max_frames: usize,
+    ) -> Self {
+        Self {
+            target,
+            name: name.to_string(),
+            dir: dir.into(),
+            every: every.max(1),
+            max_frames: max_frames.max(1),
+            ticks: 0,
+            dropped: 0,
+            frames: VecDeque::new(),
+        }
+    }
+
+    /// Recorder configured by the environment, `None` unless `AOC_RENDER_DIR` is set.
+    pub fn from_env(target: &'static str, name: &str) -> Option<Self> {
+        let dir = std::env::var_os(IMAGE_DIR_VAR)?;
+
+        let var = |name: &str, default: usize| match std::env::var(name) {
+            Ok(value) => value.parse().unwrap_or_else(|_| {
+                warn!(target: target, "Ignoring invalid {name}={value}");
+                default
+            }),
+            Err(_) => default,
+        };
+
+        Some(Self::new(
+            target,
+            name,
+            dir,
+            var(FRAME_EVERY_VAR, 1),
+            var(MAX_FRAMES_VAR, DEFAULT_MAX_FRAMES),
+        ))
+    }
+
+    /// Counts a step of the simulation, true if its frame should be pushed.
+    pub fn tick(&mut self) -> bool {
+        let keep = self.ticks.is_multiple_of(self.every);
+        self.ticks += 1;
+        keep
+    }
+
+    /// Adds a frame, dropping the oldest one if the recording is full.
+    pub fn push<T>(&mut self, renderer: &Renderer<T>) {
+        if self.frames.len() == self.max_frames {
+            self.frames.pop_front();
+            self.dropped += 1;
+        }
+
+        self.frames.push_back(renderer.glyphs());
+    }
+
+    fn size(&self) -> (usize, usize) {
+        self.frames.iter().fold((0, 0), |(width, height), frame| {
+            let frame_width = frame.iter().map(Vec::len).max().unwrap_or(0);
+            (width.max(frame_width), height.max(frame.len()))
+        })
+    }
+
+    /// The recording as an asciicast v2 file, one event redrawing the whole screen per frame.
+    pub fn to_asciicast(&self) -> String {
+        let (width, height) = self.size();
+        let mut cast = format!("{{\"version\": 2, \"width\": {width}, \"height\": {height}}}\n");
+
+        for (i, frame) in self.frames.iter().enumerate() {
+            let mut screen = String::from(if i == 0 { "\x1B[2J\x1B[H" } else { "\x1B[H" });
+
+            for (y, row) in frame.iter().enumerate() {
+                if y > 0 {
+                    screen.push_str("\r\n");
+                }
+                for glyph in row {
+                    glyph.write_ansi(&mut screen);
+                }
+            }
+
+            let time = i as f64 / FPS as f64;
+            let _ = writeln!(cast, "[{time:.3}, \"o\", {}]", json_string(&screen));
+        }
+
+        cast
+    }
+
+    /// The recording as an endlessly looping animated GIF, each cell a square of `cell_size`
+    /// pixels.
+    pub fn to_gif(&self, cell_size: usize) -> Vec<u8> {
+        let (width, height) = self.size();
+        let (width, height) = (width * cell_size, height * cell_size);
+
+        let frames = self
+            .frames
+            .iter()
+            .map(|frame| {
+                let mut pixels = vec![Rgb::BLACK; width * height];
+                for (y, row) in frame.iter().enumerate() {
+                    for (x, glyph) in row.iter().enumerate() {
+                        let color = glyph.pixel();
+                        for dy in 0..cell_size {
+                            let start = (y * cell_size + dy) * width + x * cell_size;
+                            pixels[start..start + cell_size].fill(color);
+                        }
+                    }
+                }
+                pixels
+            })
+            .collect::<Vec<_>>();
+
+        let palette = Palette::new(frames.iter().flatten());
+        let mut gif = Vec::new();
+
+        gif.extend_from_slice(b"GIF89a");
+        gif.extend_from_slice(&(width as u16).to_le_bytes());
+        gif.extend_from_slice(&(height as u16).to_le_bytes());
+        // Global colour table of 256 colours, 8 bits per channel.
+        gif.extend_from_slice(&[0xF7, 0, 0]);
+        for i in 0..256 {
+            let Rgb(r, g, b) = palette.colors.get(i).copied().unwrap_or(Rgb::BLACK);
+            gif.extend_from_slice(&[r, g, b]);
+        }
+
+        // Loop forever.
+        gif.extend_from_slice(b"\x21\xFF\x0BNETSCAPE2.0\x03\x01\x00\x00\x00");
+
+        let delay = (100 / FPS) as u16;
+        for pixels in &frames {
+            gif.extend_from_slice(&[0x21, 0xF9, 0x04, 0x00]);
+            gif.extend_from_slice(&delay.to_le_bytes());
+            gif.extend_from_slice(&[0x00, 0x00]);
+
+            gif.push(0x2C);
+            gif.extend_from_slice(&[0, 0, 0, 0]);
+            gif.extend_from_slice(&(width as u16).to_le_bytes());
+            gif.extend_from_slice(&(height as u16).to_le_bytes());
+            gif.push(0x00);
+
+            let indices = pixels.iter().map(|&color| palette.index(color));
+            gif.push(8);
+            for block in lzw_literals(indices).chunks(255) {
+                gif.push(block.len() as u8);
+                gif.extend_from_slice(block);
+            }
+            gif.push(0x00);
+        }
+
+        gif.push(0x3B);
+        gif
+    }
+
+    /// Saves the recording to the render directory, logging where it went.
+    pub fn save(&self) {
+        if self.frames.is_empty() {
+            return;
+        }
+
+        if self.dropped > 0 {
+            info!(
+                target: self.target,
+                "Recording {} kept the last {} frames, raise {MAX_FRAMES_VAR} or \
+                 {FRAME_EVERY_VAR} to see more",
+                self.name,
+                self.frames.len()
+            );
+        }
+
+        let cast = self.dir.join(format!("{}.cast", self.name));
+        let gif = self.dir.join(format!("{}.gif", self.name));
+
+        let write = || -> Result<()> {
+            std::fs::write(&cast, self.to_asciicast())?;
+            std::fs::write(&gif, self.to_gif(CELL_SIZE))?;
+            Ok(())
+        };
+
+        match write() {
+            Ok(()) => info!(
+                target: self.target,
+                "Saved {} frames to {} and {}",
+                self.frames.len(),
+                cast.display(),
+                gif.display()
+            ),
+            Err(e) => warn!(target: self.target, "Failed to save {}: {e}", self.name),
+        }
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::from("\"");
+
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if c.is_control() => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+
+    json.push('"');
+    json
+}
+
+/// The colours of a GIF, at most 256 of them. Colours beyond that are drawn with the closest
+/// one in the palette.
+struct Palette {
+    colors: Vec<Rgb>,
+}
+
+impl Palette {
+    fn new<'a>(pixels: impl Iterator<Item = &'a Rgb>) -> Self {
+        let mut colors = Vec::new();
+
+        for &color in pixels {
+            if colors.len() < 256 && !colors.contains(&color) {
+                colors.push(color);
+            }
+        }
+
+        Self { colors }
+    }
+
+    fn index(&self, color: Rgb) -> u8 {
+        let distance = |&Rgb(r, g, b): &Rgb| {
+            let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+            d(r, color.0) + d(g, color.1) + d(b, color.2)
+        };
+
+        match self.colors.iter().position(|&c| c == color) {
+            Some(i) => i as u8,
+            None => (0..self.colors.len())
+                .min_by_key(|&i| distance(&self.colors[i]))
+                .unwrap_or(0) as u8,
+        }
+    }
+}
+
+/// LZW image data with a minimum code size of 8 that only uses literal codes, resetting the code
+/// table before it would grow past 9 bit codes. Bigger than real compression, but a lot simpler.
+fn lzw_literals(indices: impl Iterator<Item = u8>) -> Vec<u8> {
+    const CLEAR: u32 = 256;
+    const END: u32 = 257;
+    const RUN: usize = 250;
+
+    let mut data = Vec::new();
+    let mut bits = 0_u32;
+    let mut bit_count = 0;
+
+    let mut emit = |code: u32| {
+        bits |= code << bit_count;
+        bit_count += 9;
+        while bit_count >= 8 {
+            data.push(bits as u8);
+            bits >>= 8;
+            bit_count -= 8;
+        }
+    };
+
+    for (i, index) in indices.enumerate() {
+        if i.is_multiple_of(RUN) {
+            emit(CLEAR);
+        }
+        emit(index as u32);
+    }
+    emit(END);
+
+    if bit_count > 0 {
+        data.push(bits as u8);
+    }
+
+    data
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::util::grid::Grid;
+
+    fn grid(rows: &[&str]) -> Grid<char> {
+        Grid::new(rows.iter().map(|row| row.chars().collect()).collect())
+    }
+
+    fn push(recorder: &mut Recorder, rows: &[&str]) {
+        recorder.push(&Renderer::new(&grid(rows), |&c| Glyph::new(c)));
+    }
+
+    fn recorder(every: usize, max_frames: usize) -> Recorder {
+        Recorder::new("test", "test", "unused", every, max_frames)
+    }
+
+    #[test]
+    fn frame_every() {
+        let mut recorder = recorder(3, 10);
+        let kept = (0..7).map(|_| recorder.tick()).collect::<Vec<_>>();
+
+        assert_eq!(kept, [true, false, false, true, false, false, true]);
+        assert!((0..5).all(|_| self::recorder(0, 10).tick()));
+    }
+
+    #[test]
+    fn keeps_the_latest_frames() {
+        let mut recorder = recorder(1, 2);
+        for frame in ["a", "b", "c"] {
+            push(&mut recorder, &[frame]);
+        }
+
+        let first = |recorder: &Recorder| {
+            recorder
+                .frames
+                .iter()
+                .map(|f| f[0][0].ch)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(first(&recorder), vec!['b', 'c']);
+        assert_eq!(recorder.dropped, 1);
+    }
+
+    #[test]
+    fn lzw_codes() {
+        // clear, 1, 2 and end as 9 bit codes, least significant bit first
+        assert_eq!(
+            lzw_literals([1, 2].into_iter()),
+            [0x00, 0x03, 0x08, 0x08, 0x08]
+        );
+
+        // a clear code every 250 literals
+        let codes = 2 + 500 + 1;
+        assert_eq!(
+            lzw_literals([0; 500].into_iter()).len(),
+            (codes * 9_usize).div_ceil(8)
+        );
+    }
+
+    #[test]
+    fn gif_layout() {
+        let mut recorder = recorder(1, 10);
+        push(&mut recorder, &["#."]);
+        push(&mut recorder, &[".#"]);
+
+        let gif = recorder.to_gif(2);
+
+        assert_eq!(&gif[..6], b"GIF89a");
+        assert_eq!(&gif[6..10], [4, 0, 2, 0]);
+        assert_eq!(gif[10], 0xF7);
+        assert_eq!(&gif[13..19], [128, 128, 128, 0, 0, 0]);
+        assert_eq!(&gif[13 + 768..13 + 768 + 3], [0x21, 0xFF, 0x0B]);
+        assert_eq!(gif.last(), Some(&0x3B));
+
+        // per frame: control extension, image descriptor, code size, a single data block of
+        // 10 codes and its terminator
+        let frame = 8 + 10 + 1 + (1 + (10 * 9_usize).div_ceil(8)) + 1;
+        assert_eq!(gif.len(), 13 + 768 + 19 + 2 * frame + 1);
+
+        let descriptor = 13 + 768 + 19 + 8;
+        assert_eq!(gif[descriptor], 0x2C);
+        assert_eq!(&gif[descriptor + 5..descriptor + 9], [4, 0, 2, 0]);
+    }
+
+    #[test]
+    fn asciicast_frames() {
+        let mut recorder = recorder(1, 10);
+        push(&mut recorder, &["ab", "c."]);
+        push(&mut recorder, &["d"]);
+
+        let cast = recorder.to_asciicast();
+        let lines = cast.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines[0], r#"{"version": 2, "width": 2, "height": 2}"#);
+        assert_eq!(
+            lines[1],
+            r#"[0.000, "o", "\u001b[2J\u001b[Hab\u000d\u000ac."]"#
+        );
+        assert_eq!(lines[2], r#"[0.100, "o", "\u001b[Hd"]"#);
+        assert_eq!(lines.len(), 3);
+    }
+}
@@ -0,0 +1,288 @@
+//! Rendering of grids as plain text, ANSI coloured text or PPM images.
+//!
+//! Days render their grids through `log` or `show`, which log them under the day's `render`
+//! target. `show` also saves them as images to `AOC_RENDER_DIR` if that's set.
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::io::IsTerminal;
+use std::path::Path;
+
+use log::{debug, log_enabled, warn, Level};
+
+use super::grid::Grid;
+use super::Vec2;
+use crate::error::Result;
+
+/// Environment variable naming the directory rendered grids are saved to as images.
+pub const IMAGE_DIR_VAR: &str = "AOC_RENDER_DIR";
+
+/// Side of a grid cell in saved images, in pixels.
+pub(super) const CELL_SIZE: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const GREY: Rgb = Rgb(128, 128, 128);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const RED: Rgb = Rgb(220, 50, 47);
+    pub const GREEN: Rgb = Rgb(80, 200, 80);
+    pub const BLUE: Rgb = Rgb(38, 139, 210);
+    pub const YELLOW: Rgb = Rgb(240, 200, 0);
+}
+
+/// How a single cell is drawn, a character with an optional colour.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Glyph {
+    pub ch: char,
+    pub color: Option<Rgb>,
+}
+
+impl Glyph {
+    pub const fn new(ch: char) -> Self {
+        Self { ch, color: None }
+    }
+
+    pub const fn colored(ch: char, color: Rgb) -> Self {
+        Self {
+            ch,
+            color: Some(color),
+        }
+    }
+
+    pub(super) fn write_ansi(&self, out: &mut String) {
+        match self.color {
+            Some(Rgb(r, g, b)) => {
+                let _ = write!(out, "\x1B[38;2;{r};{g};{b}m{}\x1B[0m", self.ch);
+            }
+            None => out.push(self.ch),
+        }
+    }
+
+    /// Colour of the cell in images. Uncoloured glyphs are black when blank (space or `.`) and
+    /// grey otherwise, so the shape of the grid stays visible.
+    pub(super) fn pixel(&self) -> Rgb {
+        match self.color {
+            Some(color) => color,
+            None if self.ch == ' ' || self.ch == '.' => Rgb::BLACK,
+            None => Rgb::GREY,
+        }
+    }
+}
+
+enum Overlay {
+    Path(HashSet<Vec2>, Glyph),
+    Highlight(HashSet<Vec2>, Rgb),
+    Cursor(Vec2, Glyph),
+}
+
+/// Renders a `Grid` with a glyph for each cell, overlays are drawn on top in the order added.
+pub struct Renderer<'a, T> {
+    grid: &'a Grid<T>,
+    glyph: Box<dyn Fn(&T) -> Glyph + 'a>,
+    overlays: Vec<Overlay>,
+}
+
+impl<'a, T> Renderer<'a, T> {
+    pub fn new(grid: &'a Grid<T>, glyph: impl Fn(&T) -> Glyph + 'a) -> Self {
+        Self {
+            grid,
+            glyph: Box::new(glyph),
+            overlays: Vec::new(),
+        }
+    }
+
+    /// Draws `glyph` over the cells of a path.
+    pub fn path(mut self, cells: impl IntoIterator<Item = Vec2>, glyph: Glyph) -> Self {
+        self.overlays
+            .push(Overlay::Path(cells.into_iter().collect(), glyph));
+        self
+    }
+
+    /// Colours the cells, keeping their characters.
+    pub fn highlight(mut self, cells: impl IntoIterator<Item = Vec2>, color: Rgb) -> Self {
+        self.overlays
+            .push(Overlay::Highlight(cells.into_iter().collect(), color));
+        self
+    }
+
+    /// Draws `glyph` over a single cell, e.g. a robot.
+    pub fn cursor(mut self, pos: Vec2, glyph: Glyph) -> Self {
+        self.overlays.push(Overlay::Cursor(pos, glyph));
+        self
+    }
+
+    fn glyph_at(&self, pos: Vec2) -> Glyph {
+        self.overlays.iter().fold(
+            (self.glyph)(&self.grid[pos]),
+            |glyph, overlay| match overlay {
+                Overlay::Path(cells, over) if cells.contains(&pos) => *over,
+                Overlay::Highlight(cells, color) if cells.contains(&pos) => Glyph {
+                    color: Some(*color),
+                    ..glyph
+                },
+                Overlay::Cursor(at, over) if *at == pos => *over,
+                _ => glyph,
+            },
+        )
+    }
+
+    fn render_rows(&self, mut cell: impl FnMut(&mut String, Glyph)) -> String {
+        let mut out = String::new();
+
+        for y in 0..self.grid.height() {
+            for x in 0..self.grid.width() {
+                cell(&mut out, self.glyph_at(Vec2::cell(x, y)));
+            }
+            out.push('\n');
+        }
+
+        out
+    }
+
+    /// The glyphs of all the cells, row by row, overlays applied.
+    pub fn glyphs(&self) -> Vec<Vec<Glyph>> {
+        (0..self.grid.height())
+            .map(|y| {
+                (0..self.grid.width())
+                    .map(|x| self.glyph_at(Vec2::cell(x, y)))
+                    .collect()
+            })
+            .collect()
+    }
+
+    pub fn to_text(&self) -> String {
+        self.render_rows(|out, glyph| out.push(glyph.ch))
+    }
+
+    pub fn to_ansi(&self) -> String {
+        self.render_rows(|out, glyph| glyph.write_ansi(out))
+    }
+
+    /// Binary PPM (P6) image with each cell a square of `cell_size` pixels.
+    pub fn to_ppm(&self, cell_size: usize) -> Vec<u8> {
+        let width = self.grid.width() * cell_size;
+        let height = self.grid.height() * cell_size;
+
+        let mut image = format!("P6\n{width} {height}\n255\n").into_bytes();
+
+        for y in 0..self.grid.height() {
+            let row = (0..self.grid.width())
+                .flat_map(|x| {
+                    let Rgb(r, g, b) = self.glyph_at(Vec2::cell(x, y)).pixel();
+                    [r, g, b].repeat(cell_size)
+                })
+                .collect::<Vec<_>>();
+
+            for _ in 0..cell_size {
+                image.extend_from_slice(&row);
+            }
+        }
+
+        image
+    }
+
+    pub fn write_ppm(&self, path: impl AsRef<Path>, cell_size: usize) -> Result<()> {
+        Ok(std::fs::write(path, self.to_ppm(cell_size))?)
+    }
+}
+
+/// Whether `show` would do anything for `target`, so that days only build what they render.
+pub fn enabled(target: &str) -> bool {
+    log_enabled!(target: target, Level::Debug) || std::env::var_os(IMAGE_DIR_VAR).is_some()
+}
+
+/// Logs the rendered grid at debug level under `target`, coloured if stderr is a terminal.
+pub fn log<T>(target: &str, title: &str, renderer: &Renderer<T>) {
+    if log_enabled!(target: target, Level::Debug) {
+        let text = if std::io::stderr().is_terminal() {
+            renderer.to_ansi()
+        } else {
+            renderer.to_text()
+        };
+
+        debug!(target: target, "{title}:\n{text}");
+    }
+}
+
+/// Logs the rendered grid like `log` and saves it as `<name>.ppm` to the `AOC_RENDER_DIR`
+/// directory if that's set.
+pub fn show<T>(target: &str, name: &str, renderer: &Renderer<T>) {
+    log(target, name, renderer);
+
+    if let Some(dir) = std::env::var_os(IMAGE_DIR_VAR) {
+        let path = Path::new(&dir).join(format!("{name}.ppm"));
+
+        if let Err(e) = renderer.write_ppm(&path, CELL_SIZE) {
+            warn!(target: target, "Failed to save {}: {e}", path.display());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn grid() -> Grid<char> {
+        Grid::new(vec!["#.#".chars().collect(), "..#".chars().collect()])
+    }
+
+    #[test]
+    fn text() {
+        let grid = grid();
+        let renderer = Renderer::new(&grid, |&c| Glyph::new(c));
+
+        assert_eq!(renderer.to_text(), "#.#\n..#\n");
+    }
+
+    #[test]
+    fn overlays_drawn_in_order() {
+        let grid = grid();
+        let renderer = Renderer::new(&grid, |&c| Glyph::new(c))
+            .path([Vec2::new(0, 1), Vec2::new(1, 1)], Glyph::new('o'))
+            .highlight([Vec2::new(1, 1), Vec2::new(2, 1)], Rgb::RED)
+            .cursor(Vec2::new(1, 1), Glyph::new('@'));
+
+        assert_eq!(renderer.to_text(), "#.#\no@#\n");
+        assert_eq!(
+            renderer.glyphs()[1],
+            [
+                Glyph::new('o'),
+                Glyph::new('@'),
+                Glyph::colored('#', Rgb::RED)
+            ]
+        );
+    }
+
+    #[test]
+    fn ansi_colours() {
+        let grid = Grid::new(vec![vec!['a', 'b']]);
+        let renderer = Renderer::new(&grid, |&c| Glyph::new(c))
+            .cursor(Vec2::new(1, 0), Glyph::colored('x', Rgb::GREEN));
+
+        assert_eq!(renderer.to_ansi(), "a\x1B[38;2;80;200;80mx\x1B[0m\n");
+    }
+
+    #[test]
+    fn ppm() {
+        let grid = grid();
+        let renderer =
+            Renderer::new(&grid, |&c| Glyph::new(c)).highlight([Vec2::new(1, 0)], Rgb::BLUE);
+
+        let image = renderer.to_ppm(2);
+        let header = b"P6\n6 4\n255\n";
+
+        assert!(image.starts_with(header));
+        assert_eq!(image.len(), header.len() + 6 * 4 * 3);
+
+        // the first pixel row: a grey wall, the blue highlighted floor, another grey wall
+        let grey = [128, 128, 128].repeat(2);
+        let blue = [38, 139, 210].repeat(2);
+        assert_eq!(
+            image[header.len()..header.len() + 18],
+            [grey.clone(), blue, grey].concat()
+        );
+    }
+}
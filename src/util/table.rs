@@ -0,0 +1,45 @@
+use std::fmt::{self, Display, Formatter};
+
+/// Plain text table with columns padded to the widest cell.
+pub struct Table<const N: usize> {
+    header: [String; N],
+    rows: Vec<[String; N]>,
+}
+
+impl<const N: usize> Table<N> {
+    pub fn new(header: [&str; N]) -> Self {
+        Self {
+            header: header.map(String::from),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, row: [String; N]) {
+        self.rows.push(row);
+    }
+}
+
+impl<const N: usize> Display for Table<N> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut widths = self.header.clone().map(|cell| cell.chars().count());
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let separator = widths.map(|width| "-".repeat(width));
+
+        for row in [&self.header, &separator].into_iter().chain(&self.rows) {
+            let line = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{cell:width$}"))
+                .collect::<Vec<_>>()
+                .join(" | ");
+            writeln!(f, "{}", line.trim_end())?;
+        }
+
+        Ok(())
+    }
+}
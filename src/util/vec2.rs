@@ -1,27 +1,122 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Rem, Sub, SubAssign};
+
+/// Coordinate types of `Vec2` and `Vec3`.
+pub trait Scalar:
+    Copy
+    + Ord
+    + Default
+    + std::fmt::Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+
+    fn rem_euclid(self, rhs: Self) -> Self;
+
+    /// The distance between two values, which doesn't overflow for unsigned types.
+    fn dist(self, other: Self) -> Self {
+        if self > other {
+            self - other
+        } else {
+            other - self
+        }
+    }
+}
+
+/// Coordinate types that can be negative.
+pub trait Signed: Scalar + Neg<Output = Self> {
+    fn abs(self) -> Self;
+    fn signum(self) -> Self;
+}
+
+macro_rules! scalar {
+    ($($t:ty),*) => {
+        $(impl Scalar for $t {
+            const ZERO: Self = 0;
+
+            fn rem_euclid(self, rhs: Self) -> Self {
+                <$t>::rem_euclid(self, rhs)
+            }
+        })*
+    };
+}
+
+macro_rules! signed {
+    ($($t:ty),*) => {
+        scalar!($($t),*);
+
+        $(impl Signed for $t {
+            fn abs(self) -> Self {
+                <$t>::abs(self)
+            }
+
+            fn signum(self) -> Self {
+                <$t>::signum(self)
+            }
+        })*
+    };
+}
+
+signed!(i32, i64, isize);
+scalar!(usize);
+
 #[derive(Clone, Copy, Default, Eq, Hash, PartialEq)]
-pub struct Vec2 {
-    pub x: i64,
-    pub y: i64,
+pub struct Vec2<T = i64> {
+    pub x: T,
+    pub y: T,
 }
 
-impl Vec2 {
-    pub fn new(x: i64, y: i64) -> Self {
+impl<T> Vec2<T> {
+    pub const fn new(x: T, y: T) -> Self {
         Self { x, y }
     }
+}
 
-    pub fn manhattan_dist(&self, other: &Vec2) -> i64 {
-        (self.x - other.x).abs() + (self.y - other.y).abs()
+impl Vec2 {
+    /// The position of column `x` and row `y` of a grid held in memory, whose indices always
+    /// fit in an `i64`.
+    pub fn cell(x: usize, y: usize) -> Self {
+        (x, y).try_into().expect("in-memory indices fit in i64")
     }
+}
 
-    pub fn manhattan_len(&self) -> i64 {
-        self.x.abs() + self.y.abs()
+impl<T: Scalar> Vec2<T> {
+    /// The same vector with coordinates of another type that holds all values of `T`.
+    pub fn cast<U: From<T>>(self) -> Vec2<U> {
+        Vec2::new(self.x.into(), self.y.into())
     }
 
-    pub fn abs_vec(&self) -> Vec2 {
-        Vec2::new(self.x.abs(), self.y.abs())
+    /// The same vector with coordinates of another type, `None` if they don't fit.
+    pub fn try_cast<U: TryFrom<T>>(self) -> Option<Vec2<U>> {
+        Some(Vec2::new(self.x.try_into().ok()?, self.y.try_into().ok()?))
     }
 
-    pub fn try_add(&self, other: &Vec2, bounds: &Vec2) -> Option<Vec2> {
+    pub fn manhattan_dist(&self, other: &Vec2<T>) -> T {
+        self.x.dist(other.x) + self.y.dist(other.y)
+    }
+
+    /// Distance when diagonal steps count as one, like a king's moves in chess.
+    pub fn chebyshev_dist(&self, other: &Vec2<T>) -> T {
+        self.x.dist(other.x).max(self.y.dist(other.y))
+    }
+
+    pub fn dot(&self, other: &Vec2<T>) -> T {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// Component-wise minimum.
+    pub fn min(&self, other: &Vec2<T>) -> Vec2<T> {
+        Vec2::new(self.x.min(other.x), self.y.min(other.y))
+    }
+
+    /// Component-wise maximum.
+    pub fn max(&self, other: &Vec2<T>) -> Vec2<T> {
+        Vec2::new(self.x.max(other.x), self.y.max(other.y))
+    }
+
+    pub fn try_add(&self, other: &Vec2<T>, bounds: &Vec2<T>) -> Option<Vec2<T>> {
         let res = *self + other;
         if res.inside(bounds) {
             Some(res)
@@ -30,29 +125,51 @@ impl Vec2 {
         }
     }
 
-    pub fn wrapping_add_mut(&mut self, other: &Vec2, bounds: &Vec2) {
-        *self = &(*self + other) % bounds;
+    pub fn wrapping_add_mut(&mut self, other: &Vec2<T>, bounds: &Vec2<T>) {
+        *self = (*self + other) % bounds;
+    }
+
+    pub fn inside(&self, bounds: &Vec2<T>) -> bool {
+        self.x >= T::ZERO && self.y >= T::ZERO && self.x < bounds.x && self.y < bounds.y
+    }
+}
+
+impl<T: Signed> Vec2<T> {
+    pub fn manhattan_len(&self) -> T {
+        self.x.abs() + self.y.abs()
+    }
+
+    pub fn abs_vec(&self) -> Vec2<T> {
+        Vec2::new(self.x.abs(), self.y.abs())
+    }
+
+    /// The sign of each coordinate, e.g. the single step towards `self` from the origin along
+    /// a row, column or diagonal.
+    pub fn signum(&self) -> Vec2<T> {
+        Vec2::new(self.x.signum(), self.y.signum())
+    }
+
+    /// The z coordinate of the 3D cross product, positive if `other` is clockwise from `self`
+    /// (with y growing downwards).
+    pub fn cross(&self, other: &Vec2<T>) -> T {
+        self.x * other.y - self.y * other.x
     }
 
-    // TODO: move this vec2 utils/tools or something
-    pub fn neighbours(&self) -> [Vec2; 4] {
-        [
-            *self + (0, -1),
-            *self + (0, 1),
-            *self + (-1, 0),
-            *self + (1, 0),
-        ]
+    /// The vector turned a quarter clockwise, with y growing downwards.
+    pub fn rotate_cw(&self) -> Vec2<T> {
+        Vec2::new(-self.y, self.x)
     }
 
-    pub fn inside(&self, bounds: &Vec2) -> bool {
-        self.x >= 0 && self.y >= 0 && self.x < bounds.x && self.y < bounds.y
+    /// The vector turned a quarter counter-clockwise, with y growing downwards.
+    pub fn rotate_ccw(&self) -> Vec2<T> {
+        Vec2::new(self.y, -self.x)
     }
 }
 
-impl std::ops::Mul<i64> for &Vec2 {
-    type Output = Vec2;
+impl<T: Scalar> Mul<T> for Vec2<T> {
+    type Output = Vec2<T>;
 
-    fn mul(self, scalar: i64) -> Vec2 {
+    fn mul(self, scalar: T) -> Vec2<T> {
         Vec2 {
             x: self.x * scalar,
             y: self.y * scalar,
@@ -60,10 +177,19 @@ impl std::ops::Mul<i64> for &Vec2 {
     }
 }
 
-impl std::ops::Rem<&Vec2> for &Vec2 {
-    type Output = Vec2;
+impl<T: Scalar> Mul<T> for &Vec2<T> {
+    type Output = Vec2<T>;
+
+    fn mul(self, scalar: T) -> Vec2<T> {
+        *self * scalar
+    }
+}
+
+/// Component-wise euclidean remainder, which wraps a position into `0..bounds`.
+impl<T: Scalar> Rem<Vec2<T>> for Vec2<T> {
+    type Output = Vec2<T>;
 
-    fn rem(self, other: &Vec2) -> Vec2 {
+    fn rem(self, other: Vec2<T>) -> Vec2<T> {
         Vec2 {
             x: self.x.rem_euclid(other.x),
             y: self.y.rem_euclid(other.y),
@@ -71,32 +197,69 @@ impl std::ops::Rem<&Vec2> for &Vec2 {
     }
 }
 
-impl std::fmt::Debug for Vec2 {
+impl<T: Scalar> Rem<&Vec2<T>> for Vec2<T> {
+    type Output = Vec2<T>;
+
+    fn rem(self, other: &Vec2<T>) -> Vec2<T> {
+        self % *other
+    }
+}
+
+impl<T: Scalar> Rem<Vec2<T>> for &Vec2<T> {
+    type Output = Vec2<T>;
+
+    fn rem(self, other: Vec2<T>) -> Vec2<T> {
+        *self % other
+    }
+}
+
+impl<T: Scalar> Rem<&Vec2<T>> for &Vec2<T> {
+    type Output = Vec2<T>;
+
+    fn rem(self, other: &Vec2<T>) -> Vec2<T> {
+        *self % *other
+    }
+}
+
+impl<T: Signed> Neg for Vec2<T> {
+    type Output = Vec2<T>;
+
+    fn neg(self) -> Vec2<T> {
+        Vec2::new(-self.x, -self.y)
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for Vec2<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {})", self.x, self.y)
+        write!(f, "({:?}, {:?})", self.x, self.y)
     }
 }
 
-impl From<(usize, usize)> for Vec2 {
-    fn from(t: (usize, usize)) -> Self {
-        Self {
-            x: t.0 as i64,
-            y: t.1 as i64,
-        }
+impl TryFrom<(usize, usize)> for Vec2 {
+    type Error = std::num::TryFromIntError;
+
+    fn try_from(t: (usize, usize)) -> Result<Self, Self::Error> {
+        Ok(Self::new(t.0.try_into()?, t.1.try_into()?))
     }
 }
 
-impl From<Vec2> for (usize, usize) {
-    fn from(p: Vec2) -> Self {
-        (p.x as usize, p.y as usize)
+impl TryFrom<Vec2> for (usize, usize) {
+    type Error = std::num::TryFromIntError;
+
+    fn try_from(p: Vec2) -> Result<Self, Self::Error> {
+        Ok((p.x.try_into()?, p.y.try_into()?))
     }
 }
 
-impl std::str::FromStr for Vec2 {
+impl<T> std::str::FromStr for Vec2<T>
+where
+    T: Scalar + std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<i64> = s
+        let parts: Vec<T> = s
             .split(&[',', ' ', '|'])
             .map(|s| s.trim().parse())
             .collect::<Result<_, _>>()
@@ -124,10 +287,10 @@ impl<T> std::ops::IndexMut<Vec2> for Vec<Vec<T>> {
     }
 }
 
-impl std::ops::Add<(i64, i64)> for Vec2 {
-    type Output = Vec2;
+impl<T: Scalar> Add<(T, T)> for Vec2<T> {
+    type Output = Vec2<T>;
 
-    fn add(self, rhs: (i64, i64)) -> Vec2 {
+    fn add(self, rhs: (T, T)) -> Vec2<T> {
         Vec2 {
             x: self.x + rhs.0,
             y: self.y + rhs.1,
@@ -135,37 +298,45 @@ impl std::ops::Add<(i64, i64)> for Vec2 {
     }
 }
 
-impl std::ops::Add<Vec2> for Vec2 {
-    type Output = Vec2;
+impl<T: Scalar> Add<Vec2<T>> for Vec2<T> {
+    type Output = Vec2<T>;
+
+    fn add(self, other: Vec2<T>) -> Vec2<T> {
+        Vec2 {
+            x: self.x + other.x,
+            y: self.y + other.y,
+        }
+    }
+}
+
+impl<T: Scalar> Add<&Vec2<T>> for Vec2<T> {
+    type Output = Vec2<T>;
 
-    fn add(self, other: Vec2) -> Vec2 {
-        &self + &other
+    fn add(self, other: &Vec2<T>) -> Vec2<T> {
+        self + *other
     }
 }
 
-impl std::ops::Add<&Vec2> for Vec2 {
-    type Output = Vec2;
+impl<T: Scalar> Add<Vec2<T>> for &Vec2<T> {
+    type Output = Vec2<T>;
 
-    fn add(self, other: &Vec2) -> Vec2 {
-        &self + other
+    fn add(self, other: Vec2<T>) -> Vec2<T> {
+        *self + other
     }
 }
 
-impl std::ops::Add<&Vec2> for &Vec2 {
-    type Output = Vec2;
+impl<T: Scalar> Add<&Vec2<T>> for &Vec2<T> {
+    type Output = Vec2<T>;
 
-    fn add(self, other: &Vec2) -> Vec2 {
-        Vec2 {
-            x: self.x + other.x,
-            y: self.y + other.y,
-        }
+    fn add(self, other: &Vec2<T>) -> Vec2<T> {
+        *self + *other
     }
 }
 
-impl std::ops::Sub<&Vec2> for Vec2 {
-    type Output = Vec2;
+impl<T: Scalar> Sub<Vec2<T>> for Vec2<T> {
+    type Output = Vec2<T>;
 
-    fn sub(self, other: &Vec2) -> Vec2 {
+    fn sub(self, other: Vec2<T>) -> Vec2<T> {
         Vec2 {
             x: self.x - other.x,
             y: self.y - other.y,
@@ -173,17 +344,133 @@ impl std::ops::Sub<&Vec2> for Vec2 {
     }
 }
 
-// impl partial eq for pair of T that implement partial eq
-impl<T> std::cmp::PartialEq<(T, T)> for Vec2
+impl<T: Scalar> Sub<&Vec2<T>> for Vec2<T> {
+    type Output = Vec2<T>;
+
+    fn sub(self, other: &Vec2<T>) -> Vec2<T> {
+        self - *other
+    }
+}
+
+impl<T: Scalar> Sub<Vec2<T>> for &Vec2<T> {
+    type Output = Vec2<T>;
+
+    fn sub(self, other: Vec2<T>) -> Vec2<T> {
+        *self - other
+    }
+}
+
+impl<T: Scalar> Sub<&Vec2<T>> for &Vec2<T> {
+    type Output = Vec2<T>;
+
+    fn sub(self, other: &Vec2<T>) -> Vec2<T> {
+        *self - *other
+    }
+}
+
+impl<T: Scalar> AddAssign<Vec2<T>> for Vec2<T> {
+    fn add_assign(&mut self, other: Vec2<T>) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Scalar> AddAssign<&Vec2<T>> for Vec2<T> {
+    fn add_assign(&mut self, other: &Vec2<T>) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Scalar> SubAssign<Vec2<T>> for Vec2<T> {
+    fn sub_assign(&mut self, other: Vec2<T>) {
+        *self = *self - other;
+    }
+}
+
+impl<T: Scalar> SubAssign<&Vec2<T>> for Vec2<T> {
+    fn sub_assign(&mut self, other: &Vec2<T>) {
+        *self = *self - other;
+    }
+}
+
+// impl partial eq for pair of U that convert to the coordinate type
+impl<T, U> std::cmp::PartialEq<(U, U)> for Vec2<T>
 where
     T: std::cmp::PartialEq,
-    T: Into<i64>,
-    T: Copy,
+    U: Into<T>,
+    U: Copy,
 {
-    fn eq(&self, other: &(T, T)) -> bool {
-        let x: i64 = other.0.into();
-        let y: i64 = other.1.into();
+    fn eq(&self, other: &(U, U)) -> bool {
+        let x: T = other.0.into();
+        let y: T = other.1.into();
 
         self.x == x && self.y == y
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rotation_and_cross_sign() {
+        let up = Vec2::new(0, -1);
+        let right = Vec2::new(1, 0);
+
+        assert_eq!(up.rotate_cw(), right);
+        assert_eq!(right.rotate_ccw(), up);
+        assert_eq!(up.rotate_cw().rotate_cw(), -up);
+        assert!(up.cross(&right) > 0);
+        assert!(right.cross(&up) < 0);
+        assert_eq!(up.cross(&-up), 0);
+
+        let v = Vec2::new(3, -7);
+        assert!(v.cross(&v.rotate_cw()) > 0);
+        assert!(v.cross(&v.rotate_ccw()) < 0);
+        assert_eq!(v.rotate_cw().rotate_ccw(), v);
+    }
+
+    #[test]
+    fn unsigned_distances() {
+        let a = Vec2::<usize>::new(2, 9);
+        let b = Vec2::<usize>::new(7, 4);
+
+        assert_eq!(a.manhattan_dist(&b), 10);
+        assert_eq!(b.manhattan_dist(&a), 10);
+        assert_eq!(a.chebyshev_dist(&b), 5);
+        assert_eq!(3_usize.dist(8), 5);
+        assert_eq!(8_usize.dist(3), 5);
+    }
+
+    #[test]
+    fn casts() {
+        assert_eq!(Vec2::<i32>::new(-1, 2).cast::<i64>(), Vec2::new(-1, 2));
+        assert_eq!(Vec2::new(3, 4).try_cast::<usize>(), Some(Vec2::new(3, 4)));
+        assert_eq!(Vec2::new(3, -4).try_cast::<usize>(), None);
+        assert_eq!(Vec2::new(i64::MAX, 0).try_cast::<i32>(), None);
+
+        assert_eq!(Vec2::try_from((3_usize, 4_usize)), Ok(Vec2::new(3, 4)));
+        assert!(Vec2::try_from((usize::MAX, 0)).is_err());
+        assert_eq!(<(usize, usize)>::try_from(Vec2::new(3, 4)), Ok((3, 4)));
+        assert!(<(usize, usize)>::try_from(Vec2::new(-1, 4)).is_err());
+        assert_eq!(Vec2::cell(5, 6), Vec2::new(5, 6));
+    }
+
+    #[test]
+    fn wrapping() {
+        let bounds = Vec2::new(11, 7);
+
+        assert_eq!(Vec2::new(-1, 15) % bounds, Vec2::new(10, 1));
+        let (pos, by_ref) = (&Vec2::new(12, -7), &bounds);
+        assert_eq!(pos % by_ref, Vec2::new(1, 0));
+
+        let mut pos = Vec2::new(10, 0);
+        pos.wrapping_add_mut(&Vec2::new(2, -3), &bounds);
+        assert_eq!(pos, Vec2::new(1, 4));
+
+        assert_eq!(Vec2::new(10, 6).try_add(&Vec2::new(1, 0), &bounds), None);
+        assert_eq!(
+            Vec2::new(9, 6).try_add(&Vec2::new(1, 0), &bounds),
+            Some(Vec2::new(10, 6))
+        );
+    }
+}
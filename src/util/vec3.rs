@@ -0,0 +1,330 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Rem, Sub, SubAssign};
+
+use super::{Scalar, Signed};
+
+#[derive(Clone, Copy, Default, Eq, Hash, PartialEq)]
+pub struct Vec3<T = i64> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Vec3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T: Scalar> Vec3<T> {
+    /// The same vector with coordinates of another type that holds all values of `T`.
+    pub fn cast<U: From<T>>(self) -> Vec3<U> {
+        Vec3::new(self.x.into(), self.y.into(), self.z.into())
+    }
+
+    /// The same vector with coordinates of another type, `None` if they don't fit.
+    pub fn try_cast<U: TryFrom<T>>(self) -> Option<Vec3<U>> {
+        Some(Vec3::new(
+            self.x.try_into().ok()?,
+            self.y.try_into().ok()?,
+            self.z.try_into().ok()?,
+        ))
+    }
+
+    pub fn manhattan_dist(&self, other: &Vec3<T>) -> T {
+        self.x.dist(other.x) + self.y.dist(other.y) + self.z.dist(other.z)
+    }
+
+    /// Distance when diagonal steps count as one.
+    pub fn chebyshev_dist(&self, other: &Vec3<T>) -> T {
+        self.x
+            .dist(other.x)
+            .max(self.y.dist(other.y))
+            .max(self.z.dist(other.z))
+    }
+
+    pub fn dot(&self, other: &Vec3<T>) -> T {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// Component-wise minimum.
+    pub fn min(&self, other: &Vec3<T>) -> Vec3<T> {
+        Vec3::new(
+            self.x.min(other.x),
+            self.y.min(other.y),
+            self.z.min(other.z),
+        )
+    }
+
+    /// Component-wise maximum.
+    pub fn max(&self, other: &Vec3<T>) -> Vec3<T> {
+        Vec3::new(
+            self.x.max(other.x),
+            self.y.max(other.y),
+            self.z.max(other.z),
+        )
+    }
+
+    pub fn inside(&self, bounds: &Vec3<T>) -> bool {
+        self.x >= T::ZERO
+            && self.y >= T::ZERO
+            && self.z >= T::ZERO
+            && self.x < bounds.x
+            && self.y < bounds.y
+            && self.z < bounds.z
+    }
+}
+
+impl<T: Signed> Vec3<T> {
+    pub fn manhattan_len(&self) -> T {
+        self.x.abs() + self.y.abs() + self.z.abs()
+    }
+
+    pub fn abs_vec(&self) -> Vec3<T> {
+        Vec3::new(self.x.abs(), self.y.abs(), self.z.abs())
+    }
+
+    pub fn signum(&self) -> Vec3<T> {
+        Vec3::new(self.x.signum(), self.y.signum(), self.z.signum())
+    }
+
+    pub fn cross(&self, other: &Vec3<T>) -> Vec3<T> {
+        Vec3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+}
+
+impl<T: Scalar> Mul<T> for Vec3<T> {
+    type Output = Vec3<T>;
+
+    fn mul(self, scalar: T) -> Vec3<T> {
+        Vec3::new(self.x * scalar, self.y * scalar, self.z * scalar)
+    }
+}
+
+impl<T: Scalar> Mul<T> for &Vec3<T> {
+    type Output = Vec3<T>;
+
+    fn mul(self, scalar: T) -> Vec3<T> {
+        *self * scalar
+    }
+}
+
+/// Component-wise euclidean remainder, which wraps a position into `0..bounds`.
+impl<T: Scalar> Rem<Vec3<T>> for Vec3<T> {
+    type Output = Vec3<T>;
+
+    fn rem(self, other: Vec3<T>) -> Vec3<T> {
+        Vec3::new(
+            self.x.rem_euclid(other.x),
+            self.y.rem_euclid(other.y),
+            self.z.rem_euclid(other.z),
+        )
+    }
+}
+
+impl<T: Scalar> Rem<&Vec3<T>> for Vec3<T> {
+    type Output = Vec3<T>;
+
+    fn rem(self, other: &Vec3<T>) -> Vec3<T> {
+        self % *other
+    }
+}
+
+impl<T: Scalar> Rem<Vec3<T>> for &Vec3<T> {
+    type Output = Vec3<T>;
+
+    fn rem(self, other: Vec3<T>) -> Vec3<T> {
+        *self % other
+    }
+}
+
+impl<T: Scalar> Rem<&Vec3<T>> for &Vec3<T> {
+    type Output = Vec3<T>;
+
+    fn rem(self, other: &Vec3<T>) -> Vec3<T> {
+        *self % *other
+    }
+}
+
+impl<T: Signed> Neg for Vec3<T> {
+    type Output = Vec3<T>;
+
+    fn neg(self) -> Vec3<T> {
+        Vec3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl<T: Scalar> Add<Vec3<T>> for Vec3<T> {
+    type Output = Vec3<T>;
+
+    fn add(self, other: Vec3<T>) -> Vec3<T> {
+        Vec3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl<T: Scalar> Add<&Vec3<T>> for Vec3<T> {
+    type Output = Vec3<T>;
+
+    fn add(self, other: &Vec3<T>) -> Vec3<T> {
+        self + *other
+    }
+}
+
+impl<T: Scalar> Add<Vec3<T>> for &Vec3<T> {
+    type Output = Vec3<T>;
+
+    fn add(self, other: Vec3<T>) -> Vec3<T> {
+        *self + other
+    }
+}
+
+impl<T: Scalar> Add<&Vec3<T>> for &Vec3<T> {
+    type Output = Vec3<T>;
+
+    fn add(self, other: &Vec3<T>) -> Vec3<T> {
+        *self + *other
+    }
+}
+
+impl<T: Scalar> Sub<Vec3<T>> for Vec3<T> {
+    type Output = Vec3<T>;
+
+    fn sub(self, other: Vec3<T>) -> Vec3<T> {
+        Vec3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl<T: Scalar> Sub<&Vec3<T>> for Vec3<T> {
+    type Output = Vec3<T>;
+
+    fn sub(self, other: &Vec3<T>) -> Vec3<T> {
+        self - *other
+    }
+}
+
+impl<T: Scalar> Sub<Vec3<T>> for &Vec3<T> {
+    type Output = Vec3<T>;
+
+    fn sub(self, other: Vec3<T>) -> Vec3<T> {
+        *self - other
+    }
+}
+
+impl<T: Scalar> Sub<&Vec3<T>> for &Vec3<T> {
+    type Output = Vec3<T>;
+
+    fn sub(self, other: &Vec3<T>) -> Vec3<T> {
+        *self - *other
+    }
+}
+
+impl<T: Scalar> AddAssign<Vec3<T>> for Vec3<T> {
+    fn add_assign(&mut self, other: Vec3<T>) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Scalar> AddAssign<&Vec3<T>> for Vec3<T> {
+    fn add_assign(&mut self, other: &Vec3<T>) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Scalar> SubAssign<Vec3<T>> for Vec3<T> {
+    fn sub_assign(&mut self, other: Vec3<T>) {
+        *self = *self - other;
+    }
+}
+
+impl<T: Scalar> SubAssign<&Vec3<T>> for Vec3<T> {
+    fn sub_assign(&mut self, other: &Vec3<T>) {
+        *self = *self - other;
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for Vec3<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({:?}, {:?}, {:?})", self.x, self.y, self.z)
+    }
+}
+
+impl<T> std::str::FromStr for Vec3<T>
+where
+    T: Scalar + std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<T> = s
+            .split(&[',', ' ', '|'])
+            .map(|s| s.trim().parse())
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("invalid Vec3: {}", e))?;
+
+        if let [x, y, z] = parts[..] {
+            Ok(Self::new(x, y, z))
+        } else {
+            Err(format!("invalid Vec3: {:?}", parts))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cross_is_right_handed() {
+        let x = Vec3::new(1, 0, 0);
+        let y = Vec3::new(0, 1, 0);
+        let z = Vec3::new(0, 0, 1);
+
+        assert_eq!(x.cross(&y), z);
+        assert_eq!(y.cross(&z), x);
+        assert_eq!(y.cross(&x), -z);
+        assert_eq!(x.cross(&x), Vec3::default());
+    }
+
+    #[test]
+    fn unsigned_distances() {
+        let a = Vec3::<usize>::new(2, 9, 4);
+        let b = Vec3::<usize>::new(7, 4, 4);
+
+        assert_eq!(a.manhattan_dist(&b), 10);
+        assert_eq!(b.manhattan_dist(&a), 10);
+        assert_eq!(a.chebyshev_dist(&b), 5);
+    }
+
+    #[test]
+    fn casts() {
+        assert_eq!(
+            Vec3::<i32>::new(-1, 2, 3).cast::<i64>(),
+            Vec3::new(-1, 2, 3)
+        );
+        assert_eq!(
+            Vec3::new(1, 2, 3).try_cast::<usize>(),
+            Some(Vec3::new(1, 2, 3))
+        );
+        assert_eq!(Vec3::new(1, 2, -3).try_cast::<usize>(), None);
+        assert_eq!(Vec3::new(0, i64::MIN, 0).try_cast::<i32>(), None);
+    }
+
+    #[test]
+    fn operators() {
+        let (a, b) = (&Vec3::new(1, 2, 3), &Vec3::new(4, -5, 6));
+
+        assert_eq!(a + b, Vec3::new(5, -3, 9));
+        assert_eq!(a - *b, Vec3::new(-3, 7, -3));
+        assert_eq!(a * 2, Vec3::new(2, 4, 6));
+        assert_eq!(b % Vec3::new(3, 3, 4), Vec3::new(1, 1, 2));
+
+        let mut c = *a;
+        c += b;
+        c -= a;
+        assert_eq!(c, *b);
+    }
+}